@@ -7,29 +7,254 @@ use sp_std::vec::Vec;
 
 #[runtime_interface]
 pub trait ForestProofVerify {
+    /// Decode the CBOR encoded block `header`, returns the cid of the block, its height,
+    /// parents, messages root, parent state root and parent message receipts root
+    #[allow(clippy::type_complexity)]
+    fn decode_block_header(
+        header: Vec<u8>,
+    ) -> Option<(Vec<u8>, i64, Vec<Vec<u8>>, Vec<u8>, Vec<u8>, Vec<u8>)> {
+        let (cid, header) = filecoindot_proofs::decode_block_header(&header).ok()?;
+        Some((
+            cid.to_bytes(),
+            header.height,
+            header.parents.iter().map(|p| p.to_bytes()).collect(),
+            header.messages.to_bytes(),
+            header.parent_state_root.to_bytes(),
+            header.parent_message_receipts.to_bytes(),
+        ))
+    }
+
     fn verify_receipt(proof: Vec<Vec<u8>>, cid: Vec<u8>) -> Option<()> {
         use filecoindot_proofs::{ForestAmtAdaptedNode, ProofVerify, Verify};
         ProofVerify::verify_proof::<ForestAmtAdaptedNode<String>>(proof, cid).ok()
     }
 
+    /// Verify the message receipt at `index` of the receipts `root` of a verified block
+    #[version(2)]
+    fn verify_receipt(proof: Vec<Vec<u8>>, root: Vec<u8>, index: u64) -> Option<()> {
+        use filecoindot_proofs::{ProofVerify, ReceiptNodeType, Verify};
+        ProofVerify::verify_amt_value::<ReceiptNodeType>(proof, root, index as usize).ok()?;
+        Some(())
+    }
+
     fn verify_state(proof: Vec<Vec<u8>>, cid: Vec<u8>) -> Option<()> {
         use filecoindot_proofs::{HAMTNodeType, ProofVerify, Verify};
         ProofVerify::verify_proof::<HAMTNodeType>(proof, cid).ok()
     }
 
+    /// Verify the state of the actor at `address` in the state `root` of a verified block
+    #[version(2)]
+    fn verify_state(proof: Vec<Vec<u8>>, root: Vec<u8>, address: Vec<u8>) -> Option<()> {
+        use forest_address::Address;
+        let address = Address::from_bytes(&address).ok()?;
+        filecoindot_proofs::verify_actor_state(proof, root, &address).ok()?;
+        Some(())
+    }
+
     fn verify_message(proof: Vec<Vec<u8>>, cid: Vec<u8>) -> Option<()> {
         use filecoindot_proofs::{MessageNodeType, ProofVerify, Verify};
         ProofVerify::verify_proof::<MessageNodeType>(proof, cid).ok()
     }
+
+    /// Verify the message at `index` of the secp messages if `secp`, of the BLS messages
+    /// otherwise, in the messages `root` of a verified block, returns the message cid
+    #[version(2)]
+    fn verify_message(
+        proof: Vec<Vec<u8>>,
+        root: Vec<u8>,
+        secp: bool,
+        index: u64,
+    ) -> Option<Vec<u8>> {
+        filecoindot_proofs::verify_block_message(proof, root, secp, index as usize)
+            .map(|cid| cid.to_bytes())
+            .ok()
+    }
+
+    /// Verify the state of the actor at `address`, returns the CBOR encoded state
+    fn verify_actor_state_value(
+        proof: Vec<Vec<u8>>,
+        root: Vec<u8>,
+        address: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        use forest_address::Address;
+        let address = Address::from_bytes(&address).ok()?;
+        let state = filecoindot_proofs::verify_actor_state(proof, root, &address).ok()?;
+        serde_cbor::to_vec(&state).ok()
+    }
+
+    /// Verify the state of the actor at `address`, returns its code cid, head cid,
+    /// nonce and balance
+    fn verify_actor_state(
+        proof: Vec<Vec<u8>>,
+        root: Vec<u8>,
+        address: Vec<u8>,
+    ) -> Option<(Vec<u8>, Vec<u8>, u64, u128)> {
+        use forest_address::Address;
+        use std::convert::TryFrom;
+        let address = Address::from_bytes(&address).ok()?;
+        let state = filecoindot_proofs::verify_actor_state(proof, root, &address).ok()?;
+        Some((
+            state.code.to_bytes(),
+            state.state.to_bytes(),
+            state.sequence,
+            u128::try_from(&state.balance).ok()?,
+        ))
+    }
+
+    /// Verify the proposal of the deal `deal_id` in the storage market actor, returns
+    /// its piece cid, client, provider, start epoch and end epoch
+    fn verify_deal(
+        state_proof: Vec<Vec<u8>>,
+        market_state: Vec<u8>,
+        deal_proof: Vec<Vec<u8>>,
+        state_root: Vec<u8>,
+        deal_id: u64,
+    ) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>, i64, i64)> {
+        use filecoindot_proofs::{verify_deal_proposal, ActorAmtProof};
+        let proof = ActorAmtProof {
+            state_proof,
+            actor_state: market_state,
+            amt_proof: deal_proof,
+        };
+        let deal = verify_deal_proposal(proof, state_root, deal_id).ok()?;
+        Some((
+            deal.piece_cid.to_bytes(),
+            deal.client.to_bytes(),
+            deal.provider.to_bytes(),
+            deal.start_epoch,
+            deal.end_epoch,
+        ))
+    }
+
+    /// Verify the sector `sector_number` of the miner actor at `miner`, returns its
+    /// sector number, seal proof, sealed cid, deal ids, activation and expiration
+    fn verify_sector(
+        state_proof: Vec<Vec<u8>>,
+        miner_state: Vec<u8>,
+        sector_proof: Vec<Vec<u8>>,
+        state_root: Vec<u8>,
+        miner: Vec<u8>,
+        sector_number: u64,
+    ) -> Option<(u64, i64, Vec<u8>, Vec<u64>, i64, i64)> {
+        use filecoindot_proofs::ActorAmtProof;
+        use forest_address::Address;
+        let proof = ActorAmtProof {
+            state_proof,
+            actor_state: miner_state,
+            amt_proof: sector_proof,
+        };
+        let miner = Address::from_bytes(&miner).ok()?;
+        let sector =
+            filecoindot_proofs::verify_sector(proof, state_root, &miner, sector_number).ok()?;
+        Some((
+            sector.sector_number,
+            sector.seal_proof,
+            sector.sealed_cid.to_bytes(),
+            sector.deal_ids,
+            sector.activation,
+            sector.expiration,
+        ))
+    }
+
+    /// Verify the message receipt at `index`, returns the CBOR encoded receipt
+    fn verify_message_receipt_value(
+        proof: Vec<Vec<u8>>,
+        root: Vec<u8>,
+        index: u64,
+    ) -> Option<Vec<u8>> {
+        use filecoindot_proofs::{ProofVerify, ReceiptNodeType, Verify};
+        let receipt =
+            ProofVerify::verify_amt_value::<ReceiptNodeType>(proof, root, index as usize).ok()?;
+        serde_cbor::to_vec(&receipt).ok()
+    }
+
+    /// Verify the message receipt at `index`, returns its exit code, return data and
+    /// gas used
+    fn verify_message_receipt(
+        proof: Vec<Vec<u8>>,
+        root: Vec<u8>,
+        index: u64,
+    ) -> Option<(u64, Vec<u8>, i64)> {
+        use filecoindot_proofs::{ProofVerify, ReceiptNodeType, Verify};
+        let receipt =
+            ProofVerify::verify_amt_value::<ReceiptNodeType>(proof, root, index as usize).ok()?;
+        Some((
+            receipt.exit_code as u64,
+            receipt.return_data.bytes().to_vec(),
+            receipt.gas_used,
+        ))
+    }
+
+    /// Verify that there is no message receipt at `index`
+    fn verify_receipt_exclusion(proof: Vec<Vec<u8>>, root: Vec<u8>, index: u64) -> Option<()> {
+        use filecoindot_proofs::{ProofVerify, ReceiptNodeType, Verify};
+        ProofVerify::verify_amt_exclusion::<ReceiptNodeType>(proof, root, index as usize).ok()
+    }
+
+    /// Verify that there is no actor at `address`
+    fn verify_state_exclusion(proof: Vec<Vec<u8>>, root: Vec<u8>, address: Vec<u8>) -> Option<()> {
+        use forest_address::Address;
+        let address = Address::from_bytes(&address).ok()?;
+        filecoindot_proofs::verify_actor_state_exclusion(proof, root, &address).ok()
+    }
+
+    /// Verify that there is no message at `index` of the secp messages if `secp`, of the
+    /// BLS messages otherwise
+    fn verify_message_exclusion(
+        proof: Vec<Vec<u8>>,
+        root: Vec<u8>,
+        secp: bool,
+        index: u64,
+    ) -> Option<()> {
+        filecoindot_proofs::verify_block_message_exclusion(proof, root, secp, index as usize).ok()
+    }
 }
 
 #[runtime_interface]
 pub trait Benchmarking {
-    fn hamt_proof_generation() -> (Vec<Vec<u8>>, Vec<u8>) {
+    fn hamt_proof_generation() -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
         filecoindot_proofs::benchmarking::hamt_proof_generation()
     }
 
-    fn amt_proof_generation(n: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+    fn amt_proof_generation(n: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
         filecoindot_proofs::benchmarking::amt_proof_generation(n as usize)
     }
+
+    fn message_receipt_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::message_receipt_proof_generation(index)
+    }
+
+    fn message_receipt_exclusion_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::message_receipt_exclusion_proof_generation(index)
+    }
+
+    fn message_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::message_proof_generation(index)
+    }
+
+    fn message_exclusion_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::message_exclusion_proof_generation(index)
+    }
+
+    fn actor_state_proof_generation(id: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::actor_state_proof_generation(id)
+    }
+
+    fn actor_state_exclusion_proof_generation(id: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::actor_state_exclusion_proof_generation(id)
+    }
+
+    fn deal_proof_generation(deal_id: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<Vec<u8>>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::deal_proof_generation(deal_id)
+    }
+
+    fn block_header_generation(height: i64, parents: Vec<Vec<u8>>, root: Vec<u8>) -> Vec<u8> {
+        filecoindot_proofs::benchmarking::block_header_generation(height, parents, root)
+    }
+
+    fn sector_proof_generation(
+        sector_number: u64,
+    ) -> (Vec<u8>, Vec<Vec<u8>>, Vec<u8>, Vec<Vec<u8>>, Vec<u8>) {
+        filecoindot_proofs::benchmarking::sector_proof_generation(sector_number)
+    }
 }
@@ -0,0 +1,602 @@
+// Copyright 2021 ChainSafe Systems
+// SPDX-License-Identifier: LGPL-3.0-only
+
+//! Proofs of the values stored in the state of the builtin filecoin actors
+//!
+//! The state of an actor is not a trie on its own, it is a CBOR tuple linking to
+//! the tries of the actor. A value in one of these tries is proven by chaining:
+//!
+//! * a proof of the actor in the state tree of the state root, see `verify_actor_state`
+//! * the raw state of the actor, i.e. the `head` of the actor
+//! * an AMT proof of the value under the trie linked from the state of the actor
+
+use crate::errors::Error;
+use crate::forest_amt_adaptor::ForestAmtAdaptedNode;
+use crate::generate::{generate_amt_proof, generate_state_proof};
+use crate::state::verify_actor_state;
+use crate::traits::{BlockStore, Verify};
+use crate::verify::ProofVerify;
+use cid::{Cid, Code::Blake2b256};
+use forest_address::Address;
+use serde::de::{self, IgnoredAny, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_bytes::{ByteBuf, Bytes};
+use std::fmt;
+
+/// The id address of the storage market actor
+pub const STORAGE_MARKET_ACTOR_ID: u64 = 5;
+
+/// The position of the deal proposals AMT in the state of the storage market actor
+pub const MARKET_PROPOSALS_FIELD: usize = 0;
+
+/// The position of the sectors AMT in the state of a miner actor
+pub const MINER_SECTORS_FIELD: usize = 9;
+
+/// A proof of a value in an AMT linked from the state of an actor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorAmtProof {
+    /// The proof of the actor in the state tree, ending with the `StateRoot`
+    pub state_proof: Vec<Vec<u8>>,
+    /// The raw state of the actor
+    pub actor_state: Vec<u8>,
+    /// The AMT proof of the value
+    pub amt_proof: Vec<Vec<u8>>,
+}
+
+/// The label of a deal proposal, a CBOR union of a UTF-8 string and raw bytes: the
+/// actors encode it as a string up to v7, and as either of them from v8 on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DealLabel {
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl Default for DealLabel {
+    fn default() -> Self {
+        DealLabel::String(String::new())
+    }
+}
+
+impl Serialize for DealLabel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            DealLabel::String(s) => serializer.serialize_str(s),
+            DealLabel::Bytes(b) => serializer.serialize_bytes(b),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DealLabel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LabelVisitor;
+
+        impl<'de> Visitor<'de> for LabelVisitor {
+            type Value = DealLabel;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a string or bytes deal label")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<DealLabel, E> {
+                Ok(DealLabel::String(v.to_string()))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<DealLabel, E> {
+                Ok(DealLabel::String(v))
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<DealLabel, E> {
+                Ok(DealLabel::Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<DealLabel, E> {
+                Ok(DealLabel::Bytes(v))
+            }
+        }
+
+        deserializer.deserialize_any(LabelVisitor)
+    }
+}
+
+/// A storage deal proposal as stored in the proposals AMT of the storage market actor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DealProposal {
+    pub piece_cid: Cid,
+    pub piece_size: u64,
+    pub verified_deal: bool,
+    pub client: Address,
+    pub provider: Address,
+    pub label: DealLabel,
+    pub start_epoch: i64,
+    pub end_epoch: i64,
+    /// The big integer bytes of the storage price per epoch
+    pub storage_price_per_epoch: Vec<u8>,
+    /// The big integer bytes of the provider collateral
+    pub provider_collateral: Vec<u8>,
+    /// The big integer bytes of the client collateral
+    pub client_collateral: Vec<u8>,
+}
+
+impl Serialize for DealProposal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (
+            &self.piece_cid,
+            self.piece_size,
+            self.verified_deal,
+            &self.client,
+            &self.provider,
+            &self.label,
+            self.start_epoch,
+            self.end_epoch,
+            Bytes::new(&self.storage_price_per_epoch),
+            Bytes::new(&self.provider_collateral),
+            Bytes::new(&self.client_collateral),
+        )
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DealProposal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (
+            piece_cid,
+            piece_size,
+            verified_deal,
+            client,
+            provider,
+            label,
+            start_epoch,
+            end_epoch,
+            storage_price_per_epoch,
+            provider_collateral,
+            client_collateral,
+        ): (
+            Cid,
+            u64,
+            bool,
+            Address,
+            Address,
+            DealLabel,
+            i64,
+            i64,
+            ByteBuf,
+            ByteBuf,
+            ByteBuf,
+        ) = Deserialize::deserialize(deserializer)?;
+        Ok(DealProposal {
+            piece_cid,
+            piece_size,
+            verified_deal,
+            client,
+            provider,
+            label,
+            start_epoch,
+            end_epoch,
+            storage_price_per_epoch: storage_price_per_epoch.into_vec(),
+            provider_collateral: provider_collateral.into_vec(),
+            client_collateral: client_collateral.into_vec(),
+        })
+    }
+}
+
+/// A sector as stored in the sectors AMT of a miner actor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectorOnChainInfo {
+    pub sector_number: u64,
+    /// The registered seal proof type
+    pub seal_proof: i64,
+    /// The CommR of the sector
+    pub sealed_cid: Cid,
+    pub deal_ids: Vec<u64>,
+    pub activation: i64,
+    pub expiration: i64,
+    /// The big integer bytes of the deal weight
+    pub deal_weight: Vec<u8>,
+    /// The big integer bytes of the verified deal weight
+    pub verified_deal_weight: Vec<u8>,
+    /// The big integer bytes of the initial pledge
+    pub initial_pledge: Vec<u8>,
+    /// The big integer bytes of the expected day reward
+    pub expected_day_reward: Vec<u8>,
+    /// The big integer bytes of the expected storage pledge
+    pub expected_storage_pledge: Vec<u8>,
+    pub replaced_sector_age: i64,
+    /// The big integer bytes of the replaced day reward
+    pub replaced_day_reward: Vec<u8>,
+    pub sector_key_cid: Option<Cid>,
+}
+
+impl Serialize for SectorOnChainInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (
+            self.sector_number,
+            self.seal_proof,
+            &self.sealed_cid,
+            &self.deal_ids,
+            self.activation,
+            self.expiration,
+            Bytes::new(&self.deal_weight),
+            Bytes::new(&self.verified_deal_weight),
+            Bytes::new(&self.initial_pledge),
+            Bytes::new(&self.expected_day_reward),
+            Bytes::new(&self.expected_storage_pledge),
+            self.replaced_sector_age,
+            Bytes::new(&self.replaced_day_reward),
+            &self.sector_key_cid,
+        )
+            .serialize(serializer)
+    }
+}
+
+/// The next field of a sequence, which has at least `i + 1` fields
+fn required_field<'de, A, T>(seq: &mut A, i: usize, exp: &dyn de::Expected) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(i, exp))
+}
+
+impl<'de> Deserialize<'de> for SectorOnChainInfo {
+    /// The layout of the sectors depends on the version of the miner actor: the v0
+    /// actors store the first 11 fields, v2 adds the replaced sector age and day
+    /// reward, v7 adds the sector key cid and v9 onwards append more fields. The
+    /// missing fields are left empty and the unknown trailing fields are skipped.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SectorVisitor;
+
+        impl<'de> Visitor<'de> for SectorVisitor {
+            type Value = SectorOnChainInfo;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a sector on chain info of at least 11 fields")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<SectorOnChainInfo, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let sector_number = required_field(&mut seq, 0, &self)?;
+                let seal_proof = required_field(&mut seq, 1, &self)?;
+                let sealed_cid = required_field(&mut seq, 2, &self)?;
+                let deal_ids = required_field(&mut seq, 3, &self)?;
+                let activation = required_field(&mut seq, 4, &self)?;
+                let expiration = required_field(&mut seq, 5, &self)?;
+                let deal_weight: ByteBuf = required_field(&mut seq, 6, &self)?;
+                let verified_deal_weight: ByteBuf = required_field(&mut seq, 7, &self)?;
+                let initial_pledge: ByteBuf = required_field(&mut seq, 8, &self)?;
+                let expected_day_reward: ByteBuf = required_field(&mut seq, 9, &self)?;
+                let expected_storage_pledge: ByteBuf = required_field(&mut seq, 10, &self)?;
+                let replaced_sector_age = seq.next_element()?.unwrap_or_default();
+                let replaced_day_reward: ByteBuf = seq.next_element()?.unwrap_or_default();
+                let sector_key_cid = seq.next_element()?.unwrap_or_default();
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+
+                Ok(SectorOnChainInfo {
+                    sector_number,
+                    seal_proof,
+                    sealed_cid,
+                    deal_ids,
+                    activation,
+                    expiration,
+                    deal_weight: deal_weight.into_vec(),
+                    verified_deal_weight: verified_deal_weight.into_vec(),
+                    initial_pledge: initial_pledge.into_vec(),
+                    expected_day_reward: expected_day_reward.into_vec(),
+                    expected_storage_pledge: expected_storage_pledge.into_vec(),
+                    replaced_sector_age,
+                    replaced_day_reward: replaced_day_reward.into_vec(),
+                    sector_key_cid,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(SectorVisitor)
+    }
+}
+
+/// The cid at position `field` of the raw state of an actor, the other fields
+/// of the state are skipped without being decoded.
+pub fn state_field_cid(actor_state: &[u8], field: usize) -> Result<Cid, Error> {
+    struct FieldCid(usize);
+
+    impl<'de> Visitor<'de> for FieldCid {
+        type Value = Cid;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                formatter,
+                "an actor state with a cid at position {}",
+                self.0
+            )
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Cid, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            for i in 0..self.0 {
+                seq.next_element::<IgnoredAny>()?
+                    .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            }
+            let cid = seq
+                .next_element::<Cid>()?
+                .ok_or_else(|| de::Error::invalid_length(self.0, &self))?;
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            Ok(cid)
+        }
+    }
+
+    let mut deserializer = serde_cbor::Deserializer::from_slice(actor_state);
+    Ok(deserializer.deserialize_seq(FieldCid(field))?)
+}
+
+/// Verify the value at index `i` of the AMT at position `field` of the state of the
+/// actor `address`, under the state tree of `state_root`. Returns the value.
+pub fn verify_actor_amt_value<V>(
+    proof: ActorAmtProof,
+    state_root: Vec<u8>,
+    address: &Address,
+    field: usize,
+    i: usize,
+) -> Result<V, Error>
+where
+    V: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    let actor = verify_actor_state(proof.state_proof, state_root, address)?;
+    if cid::new_from_cbor(&proof.actor_state, Blake2b256) != actor.state {
+        return Err(Error::VerificationFailed);
+    }
+
+    let root = state_field_cid(&proof.actor_state, field)?;
+    ProofVerify::verify_amt_value::<ForestAmtAdaptedNode<V>>(proof.amt_proof, root.to_bytes(), i)
+}
+
+/// Verify the proposal of the deal `deal_id` in the storage market actor, under the
+/// state tree of `state_root`. Returns the deal proposal.
+pub fn verify_deal_proposal(
+    proof: ActorAmtProof,
+    state_root: Vec<u8>,
+    deal_id: u64,
+) -> Result<DealProposal, Error> {
+    verify_actor_amt_value(
+        proof,
+        state_root,
+        &Address::new_id(STORAGE_MARKET_ACTOR_ID),
+        MARKET_PROPOSALS_FIELD,
+        deal_id as usize,
+    )
+}
+
+/// Verify the sector `sector_number` of the miner actor `miner`, under the state tree
+/// of `state_root`. Returns the sector.
+pub fn verify_sector(
+    proof: ActorAmtProof,
+    state_root: Vec<u8>,
+    miner: &Address,
+    sector_number: u64,
+) -> Result<SectorOnChainInfo, Error> {
+    verify_actor_amt_value(
+        proof,
+        state_root,
+        miner,
+        MINER_SECTORS_FIELD,
+        sector_number as usize,
+    )
+}
+
+/// Generate a proof of the value at index `i` of the AMT at position `field` of the
+/// state of the actor `address`, under the state tree of `state_root`.
+pub fn generate_actor_amt_proof<BS, V>(
+    store: &BS,
+    state_root: &Cid,
+    address: &Address,
+    field: usize,
+    i: usize,
+) -> Result<ActorAmtProof, Error>
+where
+    BS: BlockStore,
+    V: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    let state_proof = generate_state_proof(store, state_root, address)?;
+    // the actor is read from its own proof, which is a lookup in the state tree
+    let actor = verify_actor_state(state_proof.clone(), state_root.to_bytes(), address)?;
+    let actor_state = store.get_bytes(&actor.state)?;
+    let root = state_field_cid(&actor_state, field)?;
+    let amt_proof = generate_amt_proof::<_, ForestAmtAdaptedNode<V>>(store, &root, i)?;
+    Ok(ActorAmtProof {
+        state_proof,
+        actor_state,
+        amt_proof,
+    })
+}
+
+/// Generate a proof of the proposal of the deal `deal_id` in the storage market actor,
+/// under the state tree of `state_root`.
+pub fn generate_deal_proof<BS: BlockStore>(
+    store: &BS,
+    state_root: &Cid,
+    deal_id: u64,
+) -> Result<ActorAmtProof, Error> {
+    generate_actor_amt_proof::<_, DealProposal>(
+        store,
+        state_root,
+        &Address::new_id(STORAGE_MARKET_ACTOR_ID),
+        MARKET_PROPOSALS_FIELD,
+        deal_id as usize,
+    )
+}
+
+/// Generate a proof of the sector `sector_number` of the miner actor `miner`, under the
+/// state tree of `state_root`.
+pub fn generate_sector_proof<BS: BlockStore>(
+    store: &BS,
+    state_root: &Cid,
+    miner: &Address,
+    sector_number: u64,
+) -> Result<ActorAmtProof, Error> {
+    generate_actor_amt_proof::<_, SectorOnChainInfo>(
+        store,
+        state_root,
+        miner,
+        MINER_SECTORS_FIELD,
+        sector_number as usize,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarking::{
+        deal_proof_generation, deal_proposal, sector_on_chain_info, sector_proof_generation,
+    };
+    use serde_cbor::{from_slice, to_vec};
+
+    #[test]
+    fn test_verify_deal_proposal_works() {
+        let (state_proof, actor_state, amt_proof, state_root) = deal_proof_generation(42);
+        let proof = ActorAmtProof {
+            state_proof,
+            actor_state,
+            amt_proof,
+        };
+
+        let deal = verify_deal_proposal(proof.clone(), state_root.clone(), 42).unwrap();
+        assert_eq!(deal, deal_proposal(42));
+
+        // the proof does not prove another deal
+        assert!(verify_deal_proposal(proof.clone(), state_root.clone(), 43).is_err());
+
+        // the state of the market actor has to be the one in the state tree
+        let other = ActorAmtProof {
+            actor_state: to_vec(&(cid::new_from_cbor(&[0], Blake2b256), 0u64)).unwrap(),
+            ..proof.clone()
+        };
+        assert!(verify_deal_proposal(other, state_root, 42).is_err());
+
+        // the proof is bound to the state root
+        let other_root = cid::new_from_cbor(&[1, 2, 3], Blake2b256);
+        assert!(verify_deal_proposal(proof, other_root.to_bytes(), 42).is_err());
+    }
+
+    #[test]
+    fn test_verify_sector_works() {
+        let (miner, state_proof, actor_state, amt_proof, state_root) = sector_proof_generation(7);
+        let miner = Address::from_bytes(&miner).unwrap();
+        let proof = ActorAmtProof {
+            state_proof,
+            actor_state,
+            amt_proof,
+        };
+
+        let sector = verify_sector(proof.clone(), state_root.clone(), &miner, 7).unwrap();
+        assert_eq!(sector, sector_on_chain_info(7));
+
+        // the proof does not prove another sector
+        assert!(verify_sector(proof.clone(), state_root.clone(), &miner, 8).is_err());
+        // the proof does not prove the sector of another miner
+        assert!(verify_sector(proof, state_root, &Address::new_id(1), 7).is_err());
+    }
+
+    #[test]
+    fn test_deal_label_union() {
+        for label in [
+            DealLabel::String("label".to_string()),
+            DealLabel::Bytes(vec![0xff, 0, 1]),
+        ] {
+            let proposal = DealProposal {
+                label,
+                ..deal_proposal(1)
+            };
+            let decoded: DealProposal = from_slice(&to_vec(&proposal).unwrap()).unwrap();
+            assert_eq!(decoded, proposal);
+        }
+        // the label is neither a string nor bytes
+        let p = deal_proposal(1);
+        let invalid = to_vec(&(
+            p.piece_cid,
+            p.piece_size,
+            p.verified_deal,
+            p.client,
+            p.provider,
+            7u64,
+            p.start_epoch,
+            p.end_epoch,
+            ByteBuf::new(),
+            ByteBuf::new(),
+            ByteBuf::new(),
+        ))
+        .unwrap();
+        assert!(from_slice::<DealProposal>(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_sector_layouts() {
+        let sector = SectorOnChainInfo {
+            replaced_sector_age: 0,
+            replaced_day_reward: vec![],
+            sector_key_cid: None,
+            ..sector_on_chain_info(7)
+        };
+        let s = &sector;
+        let v0 = (
+            s.sector_number,
+            s.seal_proof,
+            s.sealed_cid,
+            &s.deal_ids,
+            s.activation,
+            s.expiration,
+            ByteBuf::new(),
+            ByteBuf::new(),
+            ByteBuf::new(),
+            ByteBuf::new(),
+            ByteBuf::new(),
+        );
+        // the v0 sectors stop after the expected storage pledge
+        let decoded: SectorOnChainInfo = from_slice(&to_vec(&v0).unwrap()).unwrap();
+        assert_eq!(decoded, sector);
+
+        // the fields appended by later versions are skipped
+        let mut v9: Vec<serde_cbor::Value> = from_slice(&to_vec(&sector).unwrap()).unwrap();
+        v9.push(serde_cbor::Value::Bytes(vec![1]));
+        let decoded: SectorOnChainInfo = from_slice(&to_vec(&v9).unwrap()).unwrap();
+        assert_eq!(decoded, sector);
+
+        // the fields of the v0 sectors are required
+        let mut truncated: Vec<serde_cbor::Value> = from_slice(&to_vec(&v0).unwrap()).unwrap();
+        truncated.pop();
+        assert!(from_slice::<SectorOnChainInfo>(&to_vec(&truncated).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_state_field_cid() {
+        let a = cid::new_from_cbor(&[1], Blake2b256);
+        let b = cid::new_from_cbor(&[2], Blake2b256);
+        let state = to_vec(&(a, 7u64, b, "rest")).unwrap();
+        assert_eq!(state_field_cid(&state, 0).unwrap(), a);
+        assert_eq!(state_field_cid(&state, 2).unwrap(), b);
+        // the field is not a cid
+        assert!(state_field_cid(&state, 1).is_err());
+        // the field is out of the state
+        assert!(state_field_cid(&state, 4).is_err());
+    }
+}
@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use crate::errors::Error;
-use crate::traits::{AMTNode, BlockStore};
+use crate::traits::{AMTNode, BlockStore, PathStep};
 use cid::Cid;
 use forest_encoding::de::Deserializer;
-use serde::Deserialize;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Serialize};
 
 pub fn nodes_for_height(bit_width: usize, height: usize) -> usize {
     let height_log_two = bit_width * height;
@@ -15,7 +16,9 @@ pub fn nodes_for_height(bit_width: usize, height: usize) -> usize {
     1 << height_log_two
 }
 
-const MAX_HEIGHT: usize = 8;
+pub(crate) const MAX_HEIGHT: usize = 8;
+/// An expanded node has `2^bit_width` slots, the AMTs of filecoin are far narrower
+pub(crate) const MAX_BIT_WIDTH: usize = 18;
 const MAX_INDEX: usize = (u64::MAX - 1) as usize;
 
 #[allow(dead_code)]
@@ -23,24 +26,32 @@ const MAX_INDEX: usize = (u64::MAX - 1) as usize;
 pub struct Amt<'db, BS: BlockStore, N: AMTNode> {
     node: N,
     block_store: Option<&'db BS>,
+    /// The cid the AMT root was loaded from
+    root: Option<Cid>,
     bit_width: usize,
     height: usize,
     count: usize,
 }
 
-impl<'db, 'de, BS: BlockStore, N: AMTNode + Deserialize<'de>> Deserialize<'de> for Amt<'db, BS, N> {
+impl<'db, 'de, BS: BlockStore, N: AMTNode> Deserialize<'de> for Amt<'db, BS, N> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let (bit_width, height, count, node): (_, _, _, N) =
+        let (bit_width, height, count, node): (_, _, _, N::Collapsed) =
             Deserialize::deserialize(deserializer)?;
+        if bit_width == 0 || bit_width > MAX_BIT_WIDTH {
+            return Err(DeError::custom("invalid AMT bit width"));
+        }
+        // all the nodes are laid out by the bit width of the AMT root
+        let node = N::expand(node, bit_width).map_err(DeError::custom)?;
         Ok(Self {
             bit_width,
             height,
             count,
             node,
             block_store: None,
+            root: None,
         })
     }
 }
@@ -48,7 +59,7 @@ impl<'db, 'de, BS: BlockStore, N: AMTNode + Deserialize<'de>> Deserialize<'de> f
 impl<'db, BS, N> Amt<'db, BS, N>
 where
     BS: BlockStore,
-    N: AMTNode + for<'de> Deserialize<'de>,
+    N: AMTNode + Serialize,
 {
     /// Constructs an AMT with a blockstore and a Cid of the root of the AMT
     pub fn load(cid: &Cid, block_store: &'db BS) -> Result<Self, Error> {
@@ -61,10 +72,38 @@ where
         }
 
         root.block_store = Some(block_store);
+        root.root = Some(*cid);
         Ok(root)
     }
 
-    /// Get value at index of AMT
+    /// Get the value at index `i`, `None` if there is no value at `i`
+    pub fn get(&self, i: usize) -> Result<Option<N::Value>, Error> {
+        if i >= nodes_for_height(self.bit_width, self.height + 1) {
+            return Ok(None);
+        }
+
+        let block_store = *self.block_store.as_ref().unwrap();
+        let (mut i, mut height) = (i, self.height);
+        let mut step = self.node.next_step(self.bit_width, height, i)?;
+        loop {
+            match step {
+                PathStep::Link(cid) if height > 0 => {
+                    i %= nodes_for_height(self.bit_width, height);
+                    height -= 1;
+                    let node = N::expand(block_store.get(&cid)?, self.bit_width)?;
+                    step = node.next_step(self.bit_width, height, i)?;
+                }
+                PathStep::Link(_) => return Err(Error::NotFound),
+                PathStep::Found(value) => return Ok(Some(value)),
+                PathStep::NotFound => return Ok(None),
+            }
+        }
+    }
+
+    /// Generates the path of all node bytes from the leaf that contains the index to
+    /// the root, as expected by `ProofVerify`. The last item is the AMT root, i.e.
+    /// `(bit_width, height, count, node)`, so that the proof can be matched against the
+    /// AMT root cid.
     pub fn generate_proof(&self, i: usize) -> Result<Vec<Vec<u8>>, Error> {
         if i > MAX_INDEX {
             return Err(Error::NotFound);
@@ -82,9 +121,48 @@ where
             i,
             &mut path,
         )? {
+            // the root node is not stored on its own but wrapped in the AMT root
+            path[0] = self.root_bytes()?;
+            path.reverse();
             Ok(path)
         } else {
             Err(Error::NotFound)
         }
     }
+
+    /// Generates the path of all node bytes from the node where the lookup of the index
+    /// ends, i.e. the node with an empty slot, to the root. As for `generate_proof`, the
+    /// last item is the AMT root. An index out of the range of the AMT is proven by the
+    /// AMT root alone. Returns Error::KeyExists if the index is present in the tree.
+    pub fn generate_exclusion_proof(&self, i: usize) -> Result<Vec<Vec<u8>>, Error> {
+        if i > MAX_INDEX {
+            return Err(Error::NotFound);
+        }
+
+        if i >= nodes_for_height(self.bit_width, self.height + 1) {
+            return Ok(vec![self.root_bytes()?]);
+        }
+
+        let mut path = Vec::new();
+        if self.node.path_to_key(
+            *self.block_store.as_ref().unwrap(),
+            self.bit_width,
+            self.height,
+            i,
+            &mut path,
+        )? {
+            Err(Error::KeyExists)
+        } else {
+            path[0] = self.root_bytes()?;
+            path.reverse();
+            Ok(path)
+        }
+    }
+
+    /// The raw AMT root as it is stored in the block store, re-serializing the expanded
+    /// node would not hash to the AMT root cid if the stored encoding is not canonical
+    fn root_bytes(&self) -> Result<Vec<u8>, Error> {
+        let root = self.root.as_ref().ok_or(Error::NotFound)?;
+        self.block_store.as_ref().unwrap().get_bytes(root)
+    }
 }
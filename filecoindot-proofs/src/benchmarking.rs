@@ -2,16 +2,26 @@
 // SPDX-License-Identifier: LGPL-3.0-only
 
 use crate::{
-    deserialize_to_node, Amt, ForestAdaptedBlockStorage, ForestAdaptedHashAlgo,
-    ForestAdaptedHashedBits, ForestAdaptedNode, ForestAmtAdaptedNode, GetCid, HAMTNodeType, Hamt,
+    decode_cid, deserialize_to_node, generate_amt_exclusion_proof, generate_amt_proof,
+    generate_deal_proof, generate_hamt_proof, generate_message_proof, generate_receipt_proof,
+    generate_sector_proof, generate_state_exclusion_proof, generate_state_proof, DealProposal,
+    ForestAdaptedBlockStorage, ForestAdaptedHashAlgo, ForestAmtAdaptedNode, GetCid, HAMTNodeType,
+    MessageCidNodeType, ReceiptNodeType, SectorOnChainInfo, StateRoot, TxMeta, HAMT_BIT_WIDTH,
+    MINER_SECTORS_FIELD, STATE_TREE_BIT_WIDTH, STORAGE_MARKET_ACTOR_ID,
 };
+use cid::Code::Blake2b256;
+use forest_address::Address;
+use forest_message::MessageReceipt;
+use forest_vm::{ActorState, ExitCode, Serialized, TokenAmount};
 use ipld_amt::Amt as ForestAmt;
-use ipld_blockstore::MemoryDB;
+use ipld_blockstore::{BlockStore, MemoryDB};
 use ipld_hamt::Hamt as ForestHamt;
+use serde_bytes::ByteBuf;
 use serde_cbor::from_slice;
 
+/// Generate a HAMT proof, returns the proof, the root cid and the cid of the proven node
 #[allow(clippy::type_complexity)]
-pub fn hamt_proof_generation() -> (Vec<Vec<u8>>, Vec<u8>) {
+pub fn hamt_proof_generation() -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
     let bs = MemoryDB::default();
     let mut fhamt: ForestHamt<_, _, usize> = ForestHamt::new(&bs);
 
@@ -22,22 +32,21 @@ pub fn hamt_proof_generation() -> (Vec<Vec<u8>>, Vec<u8>) {
 
     let cid = fhamt.flush().unwrap();
     let store = ForestAdaptedBlockStorage::new(bs);
-    let hamt: Hamt<
-        ForestAdaptedBlockStorage<MemoryDB>,
-        usize,
-        String,
-        ForestAdaptedHashedBits,
-        ForestAdaptedNode<usize, String, ForestAdaptedHashAlgo, _>,
-        ForestAdaptedHashAlgo,
-    > = Hamt::new(&cid, &store, 8).unwrap();
-    let mut p = hamt.generate_proof(&(max / 2)).unwrap();
-    p.reverse();
+    let p = generate_hamt_proof::<_, usize, String, HAMTNodeType, ForestAdaptedHashAlgo>(
+        &store,
+        &cid,
+        &(max / 2),
+        HAMT_BIT_WIDTH,
+    )
+    .unwrap();
     let raw_node = p.get(0).unwrap();
     let node: HAMTNodeType = deserialize_to_node(None, raw_node).unwrap();
-    (p, node.cid().unwrap().to_bytes())
+    (p, cid.to_bytes(), node.cid().unwrap().to_bytes())
 }
 
-pub fn amt_proof_generation(n: usize) -> (Vec<Vec<u8>>, Vec<u8>) {
+/// Generate an AMT proof, returns the proof, the root cid and the cid of the proven node
+#[allow(clippy::type_complexity)]
+pub fn amt_proof_generation(n: usize) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
     let bs = MemoryDB::default();
     let mut famt = ForestAmt::new(&bs);
 
@@ -48,10 +57,308 @@ pub fn amt_proof_generation(n: usize) -> (Vec<Vec<u8>>, Vec<u8>) {
 
     let cid = famt.flush().unwrap();
     let store = ForestAdaptedBlockStorage::new(bs);
-    let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
-        Amt::load(&cid, &store).unwrap();
-    let p = amt.generate_proof(n).unwrap();
+    let p = generate_amt_proof::<_, ForestAmtAdaptedNode<String>>(&store, &cid, n).unwrap();
     let raw_node = p.get(0).unwrap();
     let node: ForestAmtAdaptedNode<String> = from_slice(raw_node).unwrap();
-    (p, node.cid().unwrap().to_bytes())
+    (p, cid.to_bytes(), node.cid().unwrap().to_bytes())
+}
+
+/// The message receipt at `index` generated by `message_receipt_proof_generation`, the
+/// messages of even indexes succeed and the ones of odd indexes fail
+pub fn message_receipt(index: u64) -> MessageReceipt {
+    MessageReceipt {
+        exit_code: if index % 2 == 0 {
+            ExitCode::Ok
+        } else {
+            ExitCode::ErrIllegalArgument
+        },
+        return_data: Serialized::new(index.to_be_bytes().to_vec()),
+        gas_used: index as i64 * 1000,
+    }
+}
+
+/// Generate a proof of the message receipt at `index` in an AMT of 1000 receipts,
+/// returns the proof and the root cid
+pub fn message_receipt_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let mut famt = ForestAmt::new(&bs);
+    for i in 0..1000 {
+        famt.set(i, message_receipt(i as u64)).unwrap();
+    }
+
+    let cid = famt.flush().unwrap();
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let p = generate_receipt_proof(&store, &cid, index as usize).unwrap();
+    (p, cid.to_bytes())
+}
+
+/// Generate a proof that there is no message receipt at `index` in an AMT of 1000
+/// receipts with `index` removed, returns the proof and the root cid
+pub fn message_receipt_exclusion_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let mut famt = ForestAmt::new(&bs);
+    for i in 0..1000 {
+        if i as u64 != index {
+            famt.set(i, message_receipt(i as u64)).unwrap();
+        }
+    }
+
+    let cid = famt.flush().unwrap();
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let p =
+        generate_amt_exclusion_proof::<_, ReceiptNodeType>(&store, &cid, index as usize).unwrap();
+    (p, cid.to_bytes())
+}
+
+/// The cid of the message at `index` generated by `message_proof_generation`
+pub fn message_cid(index: u64) -> cid::Cid {
+    cid::new_from_cbor(&index.to_be_bytes(), Blake2b256)
+}
+
+/// Store the messages of a block with 10 BLS messages and 1000 secp messages, without
+/// the secp message at `skip`, returns the `TxMeta` of the block and its cid
+fn block_messages(bs: &MemoryDB, skip: Option<u64>) -> (TxMeta, cid::Cid) {
+    let mut bls = ForestAmt::new(bs);
+    for i in 0..10 {
+        bls.set(i, cid::new_from_cbor(&[i as u8], Blake2b256))
+            .unwrap();
+    }
+    let mut secp = ForestAmt::new(bs);
+    for i in 0..1000 {
+        if Some(i as u64) != skip {
+            secp.set(i, message_cid(i as u64)).unwrap();
+        }
+    }
+    let tx_meta = TxMeta {
+        bls_messages: bls.flush().unwrap(),
+        secp_messages: secp.flush().unwrap(),
+    };
+    let messages_root = bs.put(&tx_meta, Blake2b256).unwrap();
+    (tx_meta, messages_root)
+}
+
+/// Generate a proof of the secp message at `index` in the messages of a block with 10
+/// BLS messages and 1000 secp messages, returns the proof, the messages root and the
+/// cid of the message
+#[allow(clippy::type_complexity)]
+pub fn message_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let (_, messages_root) = block_messages(&bs, None);
+
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let message = message_cid(index);
+    let (p, _, _) = generate_message_proof(&store, &messages_root, &message).unwrap();
+    (p, messages_root.to_bytes(), message.to_bytes())
+}
+
+/// Generate a proof that there is no secp message at `index` in the messages of a block
+/// generated as in `message_proof_generation` with `index` removed, returns the proof and
+/// the messages root
+pub fn message_exclusion_proof_generation(index: u64) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let (tx_meta, messages_root) = block_messages(&bs, Some(index));
+
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let mut p = generate_amt_exclusion_proof::<_, MessageCidNodeType>(
+        &store,
+        &tx_meta.secp_messages,
+        index as usize,
+    )
+    .unwrap();
+    p.push(serde_cbor::to_vec(&tx_meta).unwrap());
+    (p, messages_root.to_bytes())
+}
+
+/// Store the `StateRoot` of a state tree of version 4 linking to the HAMT of the actors
+/// `actors`, as on mainnet. Returns the state root.
+fn put_state_root(bs: &MemoryDB, actors: cid::Cid) -> cid::Cid {
+    let root = StateRoot {
+        version: 4,
+        actors,
+        info: bs.put(&Vec::<()>::new(), Blake2b256).unwrap(),
+    };
+    bs.put(&root, Blake2b256).unwrap()
+}
+
+/// Store a state tree of 999 actors, the actor of id `i` has the code cid of `[1]`, the
+/// head cid of `[2]`, and both its nonce and balance set to `i`. Returns the state root.
+fn state_tree(bs: &MemoryDB) -> cid::Cid {
+    let mut fhamt: ForestHamt<_, ActorState, Address> =
+        ForestHamt::new_with_bit_width(bs, STATE_TREE_BIT_WIDTH);
+
+    let code = cid::new_from_cbor(&[1], Blake2b256);
+    let head = cid::new_from_cbor(&[2], Blake2b256);
+    for i in 1..1000 {
+        let state = ActorState::new(code, head, TokenAmount::from(i), i);
+        fhamt.set(Address::new_id(i), state).unwrap();
+    }
+    put_state_root(bs, fhamt.flush().unwrap())
+}
+
+/// Generate a proof of the state of the actor `id` in a state tree of 1000 actors, returns
+/// the proof, the root cid and the address of the actor. The actor of `id` has the code
+/// cid of `[1]`, the head cid of `[2]`, and both its nonce and balance set to `id`.
+#[allow(clippy::type_complexity)]
+pub fn actor_state_proof_generation(id: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let cid = state_tree(&bs);
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let address = Address::new_id(id);
+    let p = generate_state_proof(&store, &cid, &address).unwrap();
+    (p, cid.to_bytes(), address.to_bytes())
+}
+
+/// Generate a proof that there is no actor `id`, from 1000 on, in the state tree of
+/// `actor_state_proof_generation`, returns the proof, the root cid and the address of
+/// the actor
+#[allow(clippy::type_complexity)]
+pub fn actor_state_exclusion_proof_generation(id: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let cid = state_tree(&bs);
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let address = Address::new_id(id);
+    let p = generate_state_exclusion_proof(&store, &cid, &address).unwrap();
+    (p, cid.to_bytes(), address.to_bytes())
+}
+
+/// The deal proposal of `deal_id` generated by `deal_proof_generation`
+pub fn deal_proposal(deal_id: u64) -> DealProposal {
+    DealProposal {
+        piece_cid: cid::new_from_cbor(&deal_id.to_be_bytes(), Blake2b256),
+        piece_size: 2048,
+        verified_deal: false,
+        client: Address::new_id(100 + deal_id),
+        provider: Address::new_id(1000 + deal_id),
+        label: Default::default(),
+        start_epoch: deal_id as i64,
+        end_epoch: deal_id as i64 + 100,
+        storage_price_per_epoch: vec![],
+        provider_collateral: vec![],
+        client_collateral: vec![],
+    }
+}
+
+/// Generate a proof of the deal `deal_id` in a storage market actor of 100 deals,
+/// returns the proof of the market actor, the state of the market actor, the proof
+/// of the deal proposal and the state root. The proposals AMT has a bit width of 5 as
+/// in the storage market actor.
+#[allow(clippy::type_complexity)]
+pub fn deal_proof_generation(deal_id: u64) -> (Vec<Vec<u8>>, Vec<u8>, Vec<Vec<u8>>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let mut proposals = ForestAmt::new_with_bit_width(&bs, 5);
+    for i in 0..100 {
+        proposals.set(i, deal_proposal(i as u64)).unwrap();
+    }
+    let proposals = proposals.flush().unwrap();
+
+    // only the proposals field of the market state is read
+    let market_state = bs.put(&(proposals, proposals, 0u64), Blake2b256).unwrap();
+    let mut state_tree: ForestHamt<_, ActorState, Address> =
+        ForestHamt::new_with_bit_width(&bs, STATE_TREE_BIT_WIDTH);
+    let code = cid::new_from_cbor(&[1], Blake2b256);
+    let market = ActorState::new(code, market_state, TokenAmount::from(0), 0);
+    state_tree
+        .set(Address::new_id(STORAGE_MARKET_ACTOR_ID), market)
+        .unwrap();
+    let state_root = put_state_root(&bs, state_tree.flush().unwrap());
+
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let proof = generate_deal_proof(&store, &state_root, deal_id).unwrap();
+    (
+        proof.state_proof,
+        proof.actor_state,
+        proof.amt_proof,
+        state_root.to_bytes(),
+    )
+}
+
+/// The id of the miner actor generated by `sector_proof_generation`
+const MINER_ACTOR_ID: u64 = 1000;
+
+/// The sector `sector_number` generated by `sector_proof_generation`
+pub fn sector_on_chain_info(sector_number: u64) -> SectorOnChainInfo {
+    SectorOnChainInfo {
+        sector_number,
+        seal_proof: 8,
+        sealed_cid: cid::new_from_cbor(&sector_number.to_be_bytes(), Blake2b256),
+        deal_ids: vec![sector_number],
+        activation: sector_number as i64,
+        expiration: sector_number as i64 + 1000,
+        deal_weight: vec![],
+        verified_deal_weight: vec![],
+        initial_pledge: vec![],
+        expected_day_reward: vec![],
+        expected_storage_pledge: vec![],
+        replaced_sector_age: 0,
+        replaced_day_reward: vec![],
+        sector_key_cid: None,
+    }
+}
+
+/// Generate a proof of the sector `sector_number` of a miner actor with 100 sectors,
+/// returns the address of the miner, the proof of the miner actor, the state of the
+/// miner actor, the proof of the sector and the state root. The sectors AMT has a bit
+/// width of 5 as in the miner actor.
+#[allow(clippy::type_complexity)]
+pub fn sector_proof_generation(
+    sector_number: u64,
+) -> (Vec<u8>, Vec<Vec<u8>>, Vec<u8>, Vec<Vec<u8>>, Vec<u8>) {
+    let bs = MemoryDB::default();
+    let mut sectors = ForestAmt::new_with_bit_width(&bs, 5);
+    for i in 0..100 {
+        sectors.set(i, sector_on_chain_info(i as u64)).unwrap();
+    }
+    let sectors = sectors.flush().unwrap();
+
+    // only the sectors field of the miner state is read, the fields before it are
+    // filled with the sectors cid as well
+    let miner_state = vec![sectors; MINER_SECTORS_FIELD + 1];
+    let miner_state = bs.put(&miner_state, Blake2b256).unwrap();
+    let mut state_tree: ForestHamt<_, ActorState, Address> =
+        ForestHamt::new_with_bit_width(&bs, STATE_TREE_BIT_WIDTH);
+    let code = cid::new_from_cbor(&[1], Blake2b256);
+    let miner = ActorState::new(code, miner_state, TokenAmount::from(0), 0);
+    let address = Address::new_id(MINER_ACTOR_ID);
+    state_tree.set(address, miner).unwrap();
+    let state_root = put_state_root(&bs, state_tree.flush().unwrap());
+
+    let store = ForestAdaptedBlockStorage::new(bs);
+    let proof = generate_sector_proof(&store, &state_root, &address, sector_number).unwrap();
+    (
+        address.to_bytes(),
+        proof.state_proof,
+        proof.actor_state,
+        proof.amt_proof,
+        state_root.to_bytes(),
+    )
+}
+
+/// Generate a CBOR encoded block header at `height` with `parents`, all the roots of
+/// the header are set to `root`. The fields that are not read by the pallet are left
+/// empty.
+pub fn block_header_generation(height: i64, parents: Vec<Vec<u8>>, root: Vec<u8>) -> Vec<u8> {
+    let parents = parents
+        .iter()
+        .map(|p| decode_cid(p).unwrap())
+        .collect::<Vec<_>>();
+    let root = decode_cid(&root).unwrap();
+    serde_cbor::to_vec(&(
+        Address::new_id(MINER_ACTOR_ID),
+        (),
+        (),
+        Vec::<()>::new(),
+        Vec::<()>::new(),
+        parents,
+        ByteBuf::new(),
+        height,
+        root,
+        root,
+        root,
+        (),
+        0u64,
+        (),
+        0u64,
+        ByteBuf::new(),
+    ))
+    .unwrap()
 }
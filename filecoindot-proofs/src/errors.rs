@@ -10,6 +10,8 @@ pub enum Error {
     MaxDepth,
     #[error("Not found")]
     NotFound,
+    #[error("Key exists")]
+    KeyExists,
     #[error("Proof verification failed")]
     VerificationFailed,
     #[error("Max height exceeded")]
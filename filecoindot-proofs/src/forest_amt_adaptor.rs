@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: LGPL-3.0-only
 use crate::amt::nodes_for_height;
 use crate::errors::Error;
-use crate::traits::{AMTNode, BlockStore, GetCid};
+use crate::traits::{AMTNode, BlockStore, ChildLinks, GetCid, PathStep};
 use cid::Cid;
 use cid::Code::Blake2b256;
 use forest_encoding::de::Deserialize;
 use forest_encoding::to_vec;
+use forest_message::MessageReceipt;
 use ipld_amt::{CollapsedNode, Link, Node as ForestNode};
 use serde::de::Error as SerdeError;
 use serde::{Deserializer, Serialize, Serializer};
@@ -42,6 +43,8 @@ where
     }
 }
 
+/// Only backs the legacy `Verify::verify_proof`, the node is expanded as a node of an AMT
+/// of the default bit width, see `AMTNode::expand` for the nodes of any AMT.
 impl<'de, V> Deserialize<'de> for ForestAmtAdaptedNode<V>
 where
     V: Serialize + for<'a> serde::Deserialize<'a>,
@@ -74,10 +77,32 @@ where
     }
 }
 
+impl<V> ChildLinks for ForestAmtAdaptedNode<V> {
+    fn child_cids(&self) -> Vec<Cid> {
+        match &self.inner {
+            ForestNode::Leaf { .. } => Vec::new(),
+            ForestNode::Link { links, .. } => links
+                .iter()
+                .filter_map(|link| match link {
+                    Some(Link::Cid { cid, .. }) => Some(*cid),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
 impl<V> AMTNode for ForestAmtAdaptedNode<V>
 where
-    V: for<'de> Deserialize<'de> + Serialize,
+    V: Clone + for<'de> Deserialize<'de> + Serialize,
 {
+    type Value = V;
+    type Collapsed = CollapsedNode<V>;
+
+    fn expand(node: CollapsedNode<V>, bit_width: usize) -> Result<Self, Error> {
+        Ok(ForestAmtAdaptedNode::new(None, node.expand(bit_width)?))
+    }
+
     fn path_to_key<S: BlockStore>(
         &self,
         store: &S,
@@ -88,12 +113,12 @@ where
     ) -> Result<bool, Error> {
         let sub_i = i / nodes_for_height(bit_width, height);
 
+        // the node is part of the path whether or not it stores the index, as the
+        // last node of the path is what proves that the index is absent
+        path.push(to_vec(self)?);
+
         match &self.inner {
-            ForestNode::Leaf { vals, .. } => {
-                vals.get(i).ok_or(Error::NotFound)?;
-                path.push(to_vec(self)?);
-                Ok(true)
-            }
+            ForestNode::Leaf { vals, .. } => Ok(matches!(vals.get(i), Some(Some(_)))),
             ForestNode::Link { links, .. } => {
                 let link = links.get(sub_i).ok_or(Error::NotFound)?;
 
@@ -116,7 +141,24 @@ where
                     // We will not process dirty as we should have read
                     // directly from the FLUSHED storage.
                     Some(Link::Dirty(_)) => Err(Error::NotFound),
-                    None => Err(Error::NotFound),
+                    None => Ok(false),
+                }
+            }
+        }
+    }
+
+    fn next_step(&self, bit_width: usize, height: usize, i: usize) -> Result<PathStep<V>, Error> {
+        match &self.inner {
+            ForestNode::Leaf { vals, .. } => match vals.get(i) {
+                Some(Some(value)) => Ok(PathStep::Found(value.clone())),
+                _ => Ok(PathStep::NotFound),
+            },
+            ForestNode::Link { links, .. } => {
+                match links.get(i / nodes_for_height(bit_width, height)) {
+                    Some(Some(Link::Cid { cid, .. })) => Ok(PathStep::Link(*cid)),
+                    // nodes decoded from a proof are never dirty
+                    Some(Some(Link::Dirty(_))) => Err(Error::VerificationFailed),
+                    _ => Ok(PathStep::NotFound),
                 }
             }
         }
@@ -159,13 +201,16 @@ where
     }
 }
 
+/// The node of the AMT of the message receipts of a block
+pub type ReceiptNodeType = ForestAmtAdaptedNode<MessageReceipt>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{Amt, ForestAdaptedBlockStorage, ProofVerify, Verify};
+    use forest_vm::{ExitCode, Serialized};
     use ipld_amt::Amt as ForestAmt;
     use ipld_blockstore::MemoryDB;
-    use serde_cbor::from_slice;
 
     #[test]
     fn test_basic_proof_generation() {
@@ -181,7 +226,7 @@ mod tests {
         let store = ForestAdaptedBlockStorage::new(bs);
         let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
             Amt::load(&cid, &store).unwrap();
-        let p = amt.generate_proof(0);
+        let p = amt.generate_proof(1);
         assert_eq!(p.is_ok(), true);
     }
 
@@ -199,13 +244,173 @@ mod tests {
         let store = ForestAdaptedBlockStorage::new(bs);
         let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
             Amt::load(&cid, &store).unwrap();
-        let p = amt.generate_proof(0).unwrap();
-        let raw_node = p.get(0).unwrap();
-        let node: ForestAmtAdaptedNode<String> = from_slice(raw_node).unwrap();
-        let r = ProofVerify::verify_proof::<ForestAmtAdaptedNode<String>>(
-            p,
-            node.cid().unwrap().to_bytes(),
-        );
+        let p = amt.generate_proof(1).unwrap();
+
+        let r = ProofVerify::verify_amt_path::<ForestAmtAdaptedNode<String>>(&p, &cid, 1);
         assert_eq!(r.is_ok(), true);
     }
+
+    #[test]
+    fn test_verify_not_ok_other_root() {
+        let bs = MemoryDB::default();
+        let mut famt = ForestAmt::new(&bs);
+
+        let max = 1000;
+        for i in 1..max {
+            famt.set(i, i.to_string()).unwrap();
+        }
+
+        let cid = famt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
+            Amt::load(&cid, &store).unwrap();
+        let p = amt.generate_proof(1).unwrap();
+
+        let other_root = cid::new_from_cbor(&[1, 2, 3], Blake2b256);
+        let r = ProofVerify::verify_amt_path::<ForestAmtAdaptedNode<String>>(&p, &other_root, 1);
+        assert_eq!(r.is_ok(), false);
+    }
+
+    #[test]
+    fn test_verify_path_works() {
+        let bs = MemoryDB::default();
+        let mut famt = ForestAmt::new(&bs);
+
+        let max = 1000;
+        for i in 1..max {
+            famt.set(i, i.to_string()).unwrap();
+        }
+
+        let cid = famt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
+            Amt::load(&cid, &store).unwrap();
+        let p = amt.generate_proof(100).unwrap();
+
+        let verify = |i: usize, p: &[Vec<u8>]| {
+            ProofVerify::verify_amt_path::<ForestAmtAdaptedNode<String>>(p, &cid, i)
+        };
+        assert_eq!(verify(100, &p).unwrap(), 100.to_string());
+        let value = ProofVerify::verify_amt_value::<ForestAmtAdaptedNode<String>>(
+            p.clone(),
+            cid.to_bytes(),
+            100,
+        );
+        assert_eq!(value.unwrap(), 100.to_string());
+        // the path of another leaf
+        assert!(verify(900, &p).is_err());
+        // the path is missing the leaf
+        assert!(verify(100, &p[1..]).is_err());
+    }
+
+    #[test]
+    fn test_verify_exclusion_works() {
+        let bs = MemoryDB::default();
+        let mut famt = ForestAmt::new(&bs);
+
+        let max = 1000;
+        for i in 1..max {
+            famt.set(i, i.to_string()).unwrap();
+        }
+        famt.delete(100).unwrap();
+
+        let cid = famt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
+            Amt::load(&cid, &store).unwrap();
+        assert!(matches!(amt.generate_proof(0), Err(Error::NotFound)));
+        assert!(matches!(
+            amt.generate_exclusion_proof(1),
+            Err(Error::KeyExists)
+        ));
+
+        let verify = |i: usize, p: &[Vec<u8>]| {
+            ProofVerify::verify_amt_exclusion_path::<ForestAmtAdaptedNode<String>>(p, &cid, i)
+        };
+        for i in [0, 100, 5000, 1 << 20] {
+            let p = amt.generate_exclusion_proof(i).unwrap();
+            assert!(verify(i, &p).is_ok());
+            assert!(
+                ProofVerify::verify_amt_exclusion::<ForestAmtAdaptedNode<String>>(
+                    p.clone(),
+                    cid.to_bytes(),
+                    i
+                )
+                .is_ok()
+            );
+        }
+
+        let p = amt.generate_exclusion_proof(100).unwrap();
+        // the proof of an empty slot does not prove the absence of its neighbours
+        assert!(verify(101, &p).is_err());
+        // the proof stops before the lookup of the index ends
+        assert!(verify(100, &p[1..]).is_err());
+        // a membership proof does not prove the absence of its index
+        let member = amt.generate_proof(101).unwrap();
+        assert!(verify(101, &member).is_err());
+    }
+
+    #[test]
+    fn test_verify_bit_width_5_works() {
+        let bs = MemoryDB::default();
+        let mut famt = ForestAmt::new_with_bit_width(&bs, 5);
+
+        let max = 5000;
+        for i in 0..max {
+            famt.set(i, i.to_string()).unwrap();
+        }
+        famt.delete(1000).unwrap();
+
+        let cid = famt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let amt: Amt<ForestAdaptedBlockStorage<MemoryDB>, ForestAmtAdaptedNode<String>> =
+            Amt::load(&cid, &store).unwrap();
+        assert_eq!(amt.get(4242).unwrap(), Some(4242.to_string()));
+        assert_eq!(amt.get(1000).unwrap(), None);
+
+        let p = amt.generate_proof(4242).unwrap();
+        // the nodes below the root are laid out by the bit width of the root
+        assert!(p.len() > 2);
+        let value =
+            ProofVerify::verify_amt_value::<ForestAmtAdaptedNode<String>>(p, cid.to_bytes(), 4242);
+        assert_eq!(value.unwrap(), 4242.to_string());
+
+        let p = amt.generate_exclusion_proof(1000).unwrap();
+        assert!(
+            ProofVerify::verify_amt_exclusion::<ForestAmtAdaptedNode<String>>(
+                p.clone(),
+                cid.to_bytes(),
+                1000
+            )
+            .is_ok()
+        );
+        assert!(
+            ProofVerify::verify_amt_exclusion_path::<ForestAmtAdaptedNode<String>>(&p, &cid, 1001)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_message_receipt_works() {
+        let bs = MemoryDB::default();
+        let mut famt = ForestAmt::new(&bs);
+
+        let max = 100;
+        for i in 0..max {
+            let receipt = MessageReceipt {
+                exit_code: ExitCode::Ok,
+                return_data: Serialized::new(vec![i as u8]),
+                gas_used: i as i64,
+            };
+            famt.set(i, receipt).unwrap();
+        }
+
+        let cid = famt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let p = crate::generate_receipt_proof(&store, &cid, 42).unwrap();
+        let receipt =
+            ProofVerify::verify_amt_value::<ReceiptNodeType>(p, cid.to_bytes(), 42).unwrap();
+        assert_eq!(receipt.gas_used, 42);
+        assert_eq!(receipt.return_data.bytes(), &[42]);
+    }
 }
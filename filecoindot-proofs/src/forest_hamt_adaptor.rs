@@ -1,11 +1,15 @@
 // Copyright 2021 ChainSafe Systems
 // SPDX-License-Identifier: LGPL-3.0-only
 use crate::errors::Error;
-use crate::traits::{BlockStore, GetCid, HAMTNode, HashAlgorithm, HashedBits};
+use crate::traits::{
+    BlockStore, ChildLinks, GetCid, HAMTNode, HashAlgorithm, HashedBits, PathStep,
+};
 use cid::Cid;
 use cid::Code::Blake2b256;
+use forest_address::Address;
 use forest_encoding::de::{Deserialize, Deserializer};
 use forest_encoding::{from_slice, to_vec};
+use forest_vm::ActorState;
 use ipld_blockstore::BlockStore as ForestBlockStore;
 use ipld_hamt::{
     Bitfield, Hash, HashAlgorithm as ForestHashAlgo, Node as ForestNode, Pointer as ForestPointer,
@@ -171,11 +175,27 @@ where
     }
 }
 
+impl<K, V, H> ChildLinks for ForestAdaptedNode<K, V, H, ForestAdaptedHashedBits>
+where
+    K: Eq + Serialize,
+    V: Serialize,
+{
+    fn child_cids(&self) -> Vec<Cid> {
+        self.raw_pointers
+            .iter()
+            .filter_map(|pointer| match pointer {
+                Pointer::Link { cid, .. } => Some(*cid),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
 impl<'a, K, V, H> HAMTNode<K, V, ForestAdaptedHashedBits>
     for ForestAdaptedNode<K, V, H, ForestAdaptedHashedBits>
 where
     K: Eq + Serialize + for<'de> serde::Deserialize<'de>,
-    V: Serialize + for<'de> serde::Deserialize<'de>,
+    V: Clone + Serialize + for<'de> serde::Deserialize<'de>,
 {
     fn path_to_key<S: BlockStore>(
         &self,
@@ -185,8 +205,11 @@ where
         bit_width: u8,
         s: &S,
     ) -> Result<bool, Error> {
-        let idx = hash_bits.next(bit_width)?;
+        // the node is part of the path whether or not it stores the key, as the
+        // last node of the path is what proves that the key is absent
+        path.push(to_vec(self)?);
 
+        let idx = hash_bits.next(bit_width)?;
         if !self.bitfield.test_bit(idx) {
             return Ok(false);
         }
@@ -195,7 +218,6 @@ where
         let child = self.get_child(cindex);
         match child {
             ForestPointer::Link { cid, cache, .. } => {
-                path.push(to_vec(self)?);
                 let n: Self;
                 if let Some(cached_node) = cache.get() {
                     n = deserialize_to_node(Some(*cid), &serialize_to_slice(cached_node)?)?;
@@ -206,17 +228,33 @@ where
                 }
             }
             ForestPointer::Dirty(n) => {
-                path.push(to_vec(self)?);
                 let n: Self = deserialize_to_node(None, &serialize_to_slice(n)?)?;
                 n.path_to_key(hash_bits, k, path, bit_width, s)
             }
+            ForestPointer::Values(key_values) => Ok(key_values.iter().any(|kv| kv.key() == k)),
+        }
+    }
+
+    fn next_step(
+        &self,
+        hash_bits: &mut ForestAdaptedHashedBits,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<PathStep<V>, Error> {
+        let idx = hash_bits.next(bit_width)?;
+
+        if !self.bitfield.test_bit(idx) {
+            return Ok(PathStep::NotFound);
+        }
+
+        match self.get_child(self.index_for_bit_pos(idx)) {
+            ForestPointer::Link { cid, .. } => Ok(PathStep::Link(*cid)),
             ForestPointer::Values(key_values) => match key_values.iter().find(|kv| kv.key() == k) {
-                Some(_) => {
-                    path.push(to_vec(self)?);
-                    Ok(true)
-                }
-                None => Ok(false),
+                Some(kv) => Ok(PathStep::Found(kv.value().clone())),
+                None => Ok(PathStep::NotFound),
             },
+            // nodes decoded from a proof are never dirty
+            ForestPointer::Dirty(_) => Err(Error::VerificationFailed),
         }
     }
 
@@ -271,8 +309,11 @@ where
     FBS: ForestBlockStore,
 {
     fn get<T: DeserializeOwned>(&self, cid: &Cid) -> Result<T, Error> {
-        let n = self.store.read(cid.to_bytes())?.ok_or(Error::NotFound)?;
-        Ok(from_slice(&n)?)
+        Ok(from_slice(&self.get_bytes(cid)?)?)
+    }
+
+    fn get_bytes(&self, cid: &Cid) -> Result<Vec<u8>, Error> {
+        self.store.read(cid.to_bytes())?.ok_or(Error::NotFound)
     }
 }
 
@@ -306,11 +347,16 @@ pub type HAMTNodeType =
 pub type MessageNodeType =
     ForestAdaptedNode<usize, String, ForestAdaptedHashAlgo, ForestAdaptedHashedBits>;
 
+/// The node of the filecoin state tree, actor states keyed by actor addresses
+pub type StateNodeType =
+    ForestAdaptedNode<Address, ActorState, ForestAdaptedHashAlgo, ForestAdaptedHashedBits>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::hamt::Hamt;
     use crate::{ProofVerify, Verify};
+    use forest_vm::TokenAmount;
     use ipld_blockstore::MemoryDB;
     use ipld_hamt::Hamt as ForestHamt;
 
@@ -416,8 +462,7 @@ mod tests {
             ForestAdaptedHashAlgo,
         > = Hamt::new(&cid, &store, 8).unwrap();
 
-        let mut p = hamt.generate_proof(&(max / 2)).unwrap();
-        p.reverse();
+        let p = hamt.generate_proof(&(max / 2)).unwrap();
 
         let raw_node = p.get(0).unwrap();
         let node: HAMTNodeType = deserialize_to_node(None, raw_node).unwrap();
@@ -446,10 +491,189 @@ mod tests {
             ForestAdaptedHashAlgo,
         > = Hamt::new(&cid, &store, 8).unwrap();
 
-        let mut p = hamt.generate_proof(&(max / 2)).unwrap();
-        p.reverse();
+        let p = hamt.generate_proof(&(max / 2)).unwrap();
         let target_cid = cid::new_from_cbor(&[1, 2, 3], Blake2b256);
         let r = ProofVerify::verify_proof::<HAMTNodeType>(p, target_cid.to_bytes());
         assert_eq!(r.is_ok(), false);
     }
+
+    #[test]
+    fn test_verify_with_root_works() {
+        let bs = MemoryDB::default();
+        let mut fhamt: ForestHamt<_, _, usize> = ForestHamt::new(&bs);
+
+        let max = 10000;
+        for i in 1..max {
+            fhamt.set(i, i.to_string()).unwrap();
+        }
+
+        let cid = fhamt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let hamt: HamtType = Hamt::new(&cid, &store, 8).unwrap();
+
+        let p = hamt.generate_proof(&(max / 2)).unwrap();
+
+        let value = ProofVerify::verify_hamt_value::<
+            usize,
+            String,
+            HAMTNodeType,
+            ForestAdaptedHashAlgo,
+        >(p.clone(), cid.to_bytes(), &(max / 2), 8);
+        assert_eq!(value.unwrap(), (max / 2).to_string());
+
+        let other_root = cid::new_from_cbor(&[1, 2, 3], Blake2b256);
+        assert!(
+            ProofVerify::verify_hamt_value::<usize, String, HAMTNodeType, ForestAdaptedHashAlgo>(
+                p,
+                other_root.to_bytes(),
+                &(max / 2),
+                8
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_path_works() {
+        let bs = MemoryDB::default();
+        let mut fhamt: ForestHamt<_, _, usize> = ForestHamt::new(&bs);
+
+        let max = 10000;
+        for i in 1..max {
+            fhamt.set(i, i.to_string()).unwrap();
+        }
+
+        let cid = fhamt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let hamt: HamtType = Hamt::new(&cid, &store, 8).unwrap();
+
+        let p = hamt.generate_proof(&(max / 2)).unwrap();
+
+        let verify = |k: usize, p: &[Vec<u8>]| {
+            ProofVerify::verify_hamt_path::<usize, String, HAMTNodeType, ForestAdaptedHashAlgo>(
+                p, &cid, &k, 8,
+            )
+        };
+        assert_eq!(verify(max / 2, &p).unwrap(), (max / 2).to_string());
+        // a key that is not in the trie
+        assert!(verify(max + 1, &p).is_err());
+        // the path is missing the root
+        assert!(verify(max / 2, &p[..p.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_verify_not_ok_unlinked_node() {
+        let bs = MemoryDB::default();
+        let mut fhamt: ForestHamt<_, _, usize> = ForestHamt::new(&bs);
+        let mut other: ForestHamt<_, _, usize> = ForestHamt::new(&bs);
+
+        let max = 10000;
+        for i in 1..max {
+            fhamt.set(i, i.to_string()).unwrap();
+            other.set(i, (i + 1).to_string()).unwrap();
+        }
+
+        let cid = fhamt.flush().unwrap();
+        let other_cid = other.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let hamt: HamtType = Hamt::new(&cid, &store, 8).unwrap();
+
+        let mut p = hamt.generate_proof(&(max / 2)).unwrap();
+
+        // append a node that is in the proof but not linked from its path
+        let other_root: HAMTNodeType = store.get(&other_cid).unwrap();
+        p.insert(0, to_vec(&other_root).unwrap());
+        let r = ProofVerify::verify_proof::<HAMTNodeType>(p, other_cid.to_bytes());
+        assert_eq!(r.is_ok(), false);
+    }
+
+    #[test]
+    fn test_verify_exclusion_works() {
+        let bs = MemoryDB::default();
+        let mut fhamt: ForestHamt<_, _, usize> = ForestHamt::new(&bs);
+
+        let max = 1000;
+        for i in 1..max {
+            fhamt.set(i, i.to_string()).unwrap();
+        }
+
+        let cid = fhamt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let hamt: HamtType = Hamt::new(&cid, &store, 8).unwrap();
+        assert!(matches!(
+            hamt.generate_exclusion_proof(&(max / 2)),
+            Err(Error::KeyExists)
+        ));
+
+        let p = hamt.generate_exclusion_proof(&(max + 1)).unwrap();
+
+        let verify = |k: usize, p: &[Vec<u8>]| {
+            ProofVerify::verify_hamt_exclusion_path::<
+                usize,
+                String,
+                HAMTNodeType,
+                ForestAdaptedHashAlgo,
+            >(p, &cid, &k, 8)
+        };
+        assert!(verify(max + 1, &p).is_ok());
+        assert!(ProofVerify::verify_hamt_exclusion::<
+            usize,
+            String,
+            HAMTNodeType,
+            ForestAdaptedHashAlgo,
+        >(p.clone(), cid.to_bytes(), &(max + 1), 8)
+        .is_ok());
+        // the key is not a member either
+        assert!(
+            ProofVerify::verify_hamt_path::<usize, String, HAMTNodeType, ForestAdaptedHashAlgo>(
+                &p,
+                &cid,
+                &(max + 1),
+                8
+            )
+            .is_err()
+        );
+        // a membership proof does not prove the absence of its key
+        let member = hamt.generate_proof(&(max / 2)).unwrap();
+        assert!(verify(max / 2, &member).is_err());
+        // the proof stops before the lookup of the key ends
+        if p.len() > 1 {
+            assert!(verify(max + 1, &p[1..]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_actor_state_works() {
+        let bs = MemoryDB::default();
+        let mut fhamt: ForestHamt<_, ActorState, Address> = ForestHamt::new(&bs);
+
+        let code = cid::new_from_cbor(&[1], Blake2b256);
+        let max = 100;
+        for i in 1..max {
+            let state = ActorState::new(code, code, TokenAmount::from(i), i);
+            fhamt.set(Address::new_id(i), state).unwrap();
+        }
+
+        let cid = fhamt.flush().unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let address = Address::new_id(max / 2);
+        let p = crate::generate_hamt_proof::<
+            _,
+            Address,
+            ActorState,
+            StateNodeType,
+            ForestAdaptedHashAlgo,
+        >(&store, &cid, &address, 8)
+        .unwrap();
+
+        let state = ProofVerify::verify_hamt_value::<
+            Address,
+            ActorState,
+            StateNodeType,
+            ForestAdaptedHashAlgo,
+        >(p, cid.to_bytes(), &address, 8)
+        .unwrap();
+        assert_eq!(state.sequence, max / 2);
+        assert_eq!(state.balance, TokenAmount::from(max / 2));
+    }
 }
@@ -2,18 +2,292 @@
 // SPDX-License-Identifier: LGPL-3.0-only
 
 //! Generation of proofs for AMT and HAMT tries
+//!
+//! All the proofs are generated in the layout expected by `ProofVerify` and the
+//! pallet: the node storing the target first and the root of the trie last. The
+//! proofs of the messages of a block are followed by the `TxMeta` of the block, and the
+//! proofs of the actors by the `StateRoot` of the block.
 
-// pub mod hamt {
-//     use crate::errors::Error;
-//     use crate::hamt::Hamt;
-//
-//     /// Generate an inclusion proof for the value in the given HAMT.
-//     pub fn generate_proof<'db, V, BS>(
-//         hamt: &Hamt<'db, BS, V>,
-//         value: V,
-//     ) -> Result<Vec<Vec<u8>>, Error> {
-//         // each node can have up to 32 children
-//
-//         todo!()
-//     }
-// }
+use crate::amt::Amt;
+use crate::errors::Error;
+use crate::hamt::Hamt;
+use crate::state::{StateRoot, STATE_TREE_BIT_WIDTH};
+use crate::traits::{AMTNode, BlockStore, HAMTNode, HashAlgorithm};
+use crate::{ForestAdaptedHashAlgo, MessageCidNodeType, ReceiptNodeType, StateNodeType, TxMeta};
+use cid::Cid;
+use forest_address::Address;
+use forest_vm::ActorState;
+use ipld_hamt::Hash;
+use serde::{Deserialize, Serialize};
+
+/// The default bit width of the forest HAMTs, the actors of the state tree are laid out
+/// by `STATE_TREE_BIT_WIDTH`
+pub const HAMT_BIT_WIDTH: u8 = 8;
+
+/// Generate an inclusion proof of `k` in the HAMT of `root`
+pub fn generate_hamt_proof<BS, K, V, N, A>(
+    store: &BS,
+    root: &Cid,
+    k: &K,
+    bit_width: u8,
+) -> Result<Vec<Vec<u8>>, Error>
+where
+    BS: BlockStore,
+    K: Eq + Hash,
+    A: HashAlgorithm,
+    N: HAMTNode<K, V, A::Output> + for<'de> Deserialize<'de>,
+{
+    let hamt: Hamt<BS, K, V, A::Output, N, A> = Hamt::new(root, store, bit_width)?;
+    hamt.generate_proof(k)
+}
+
+/// Generate an exclusion proof of `k` in the HAMT of `root`
+pub fn generate_hamt_exclusion_proof<BS, K, V, N, A>(
+    store: &BS,
+    root: &Cid,
+    k: &K,
+    bit_width: u8,
+) -> Result<Vec<Vec<u8>>, Error>
+where
+    BS: BlockStore,
+    K: Eq + Hash,
+    A: HashAlgorithm,
+    N: HAMTNode<K, V, A::Output> + for<'de> Deserialize<'de>,
+{
+    let hamt: Hamt<BS, K, V, A::Output, N, A> = Hamt::new(root, store, bit_width)?;
+    hamt.generate_exclusion_proof(k)
+}
+
+/// Generate an inclusion proof of the index `i` in the AMT of `root`
+pub fn generate_amt_proof<BS, N>(store: &BS, root: &Cid, i: usize) -> Result<Vec<Vec<u8>>, Error>
+where
+    BS: BlockStore,
+    N: AMTNode + Serialize,
+{
+    Amt::<BS, N>::load(root, store)?.generate_proof(i)
+}
+
+/// Generate an exclusion proof of the index `i` in the AMT of `root`
+pub fn generate_amt_exclusion_proof<BS, N>(
+    store: &BS,
+    root: &Cid,
+    i: usize,
+) -> Result<Vec<Vec<u8>>, Error>
+where
+    BS: BlockStore,
+    N: AMTNode + Serialize,
+{
+    Amt::<BS, N>::load(root, store)?.generate_exclusion_proof(i)
+}
+
+/// Generate a proof of the state of the actor at `address` in the state tree of
+/// `state_root`, i.e. the HAMT proof of the actor followed by the raw `StateRoot`
+pub fn generate_state_proof<BS: BlockStore>(
+    store: &BS,
+    state_root: &Cid,
+    address: &Address,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let raw_root = store.get_bytes(state_root)?;
+    let root: StateRoot = serde_cbor::from_slice(&raw_root)?;
+    let mut proof = generate_hamt_proof::<
+        _,
+        Address,
+        ActorState,
+        StateNodeType,
+        ForestAdaptedHashAlgo,
+    >(store, &root.actors, address, STATE_TREE_BIT_WIDTH)?;
+    proof.push(raw_root);
+    Ok(proof)
+}
+
+/// Generate a proof that there is no actor at `address` in the state tree of
+/// `state_root`, in the layout of `generate_state_proof`
+pub fn generate_state_exclusion_proof<BS: BlockStore>(
+    store: &BS,
+    state_root: &Cid,
+    address: &Address,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let raw_root = store.get_bytes(state_root)?;
+    let root: StateRoot = serde_cbor::from_slice(&raw_root)?;
+    let mut proof = generate_hamt_exclusion_proof::<
+        _,
+        Address,
+        ActorState,
+        StateNodeType,
+        ForestAdaptedHashAlgo,
+    >(store, &root.actors, address, STATE_TREE_BIT_WIDTH)?;
+    proof.push(raw_root);
+    Ok(proof)
+}
+
+/// Generate a proof of the message receipt at `index` in the receipts of `receipts_root`
+pub fn generate_receipt_proof<BS: BlockStore>(
+    store: &BS,
+    receipts_root: &Cid,
+    index: usize,
+) -> Result<Vec<Vec<u8>>, Error> {
+    generate_amt_proof::<_, ReceiptNodeType>(store, receipts_root, index)
+}
+
+/// Generate a proof of the message of cid `message` in the messages of `messages_root`,
+/// i.e. the AMT proof of the message cid followed by the raw `TxMeta` of the block.
+/// Returns the proof, whether the message is a secp message, and its index in the
+/// BLS or secp messages of the block.
+pub fn generate_message_proof<BS: BlockStore>(
+    store: &BS,
+    messages_root: &Cid,
+    message: &Cid,
+) -> Result<(Vec<Vec<u8>>, bool, usize), Error> {
+    let raw_tx_meta = store.get_bytes(messages_root)?;
+    let tx_meta: TxMeta = serde_cbor::from_slice(&raw_tx_meta)?;
+    for secp in [false, true] {
+        let amt = Amt::<BS, MessageCidNodeType>::load(&tx_meta.messages(secp), store)?;
+        // the messages of a block are stored at consecutive indexes from 0
+        for index in 0.. {
+            match amt.get(index)? {
+                Some(cid) if cid == *message => {
+                    let mut proof = amt.generate_proof(index)?;
+                    proof.push(raw_tx_meta);
+                    return Ok((proof, secp, index));
+                }
+                Some(_) => continue,
+                None => break,
+            }
+        }
+    }
+    Err(Error::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode_block_header, verify_actor_state, verify_block_message, ForestAdaptedBlockStorage,
+        ProofVerify, Verify,
+    };
+    use cid::Code::Blake2b256;
+    use forest_message::MessageReceipt;
+    use forest_vm::{ExitCode, Serialized, TokenAmount};
+    use ipld_amt::Amt as ForestAmt;
+    use ipld_blockstore::{BlockStore as ForestBlockStore, MemoryDB};
+    use ipld_hamt::Hamt as ForestHamt;
+    use serde_bytes::ByteBuf;
+
+    /// The cid of the `i`th message of the block, BLS or secp
+    fn message(i: u64, secp: bool) -> Cid {
+        cid::new_from_cbor(&serde_cbor::to_vec(&(i, secp)).unwrap(), Blake2b256)
+    }
+
+    /// Store the tries of a block and return its CBOR encoded header
+    fn block_header(bs: &MemoryDB) -> Vec<u8> {
+        let mut state =
+            ForestHamt::<_, ActorState, Address>::new_with_bit_width(bs, STATE_TREE_BIT_WIDTH);
+        let code = cid::new_from_cbor(&[1], Blake2b256);
+        for i in 1..100 {
+            let actor = ActorState::new(code, code, TokenAmount::from(i), i);
+            state.set(Address::new_id(i), actor).unwrap();
+        }
+
+        let mut receipts = ForestAmt::new(bs);
+        let mut bls = ForestAmt::new(bs);
+        let mut secp = ForestAmt::new(bs);
+        for i in 0..100u64 {
+            let receipt = MessageReceipt {
+                exit_code: ExitCode::Ok,
+                return_data: Serialized::new(vec![i as u8]),
+                gas_used: i as i64,
+            };
+            receipts.set(i as usize, receipt).unwrap();
+            bls.set(i as usize, message(i, false)).unwrap();
+            secp.set(i as usize, message(i, true)).unwrap();
+        }
+        let tx_meta = TxMeta {
+            bls_messages: bls.flush().unwrap(),
+            secp_messages: secp.flush().unwrap(),
+        };
+        let state_root = StateRoot {
+            version: 4,
+            actors: state.flush().unwrap(),
+            info: bs.put(&Vec::<()>::new(), Blake2b256).unwrap(),
+        };
+
+        let parent = cid::new_from_cbor(&[0], Blake2b256);
+        serde_cbor::to_vec(&(
+            Address::new_id(1000),
+            (),
+            (),
+            Vec::<()>::new(),
+            Vec::<()>::new(),
+            vec![parent],
+            ByteBuf::new(),
+            42i64,
+            bs.put(&state_root, Blake2b256).unwrap(),
+            receipts.flush().unwrap(),
+            bs.put(&tx_meta, Blake2b256).unwrap(),
+            (),
+            0u64,
+            (),
+            0u64,
+            ByteBuf::new(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generated_proofs_verify() {
+        let bs = MemoryDB::default();
+        let (_, header) = decode_block_header(&block_header(&bs)).unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+
+        let address = Address::new_id(42);
+        let p = generate_state_proof(&store, &header.parent_state_root, &address).unwrap();
+        let actor = verify_actor_state(p, header.parent_state_root.to_bytes(), &address).unwrap();
+        assert_eq!(actor.sequence, 42);
+
+        let p = generate_receipt_proof(&store, &header.parent_message_receipts, 42).unwrap();
+        let receipt = ProofVerify::verify_amt_value::<ReceiptNodeType>(
+            p,
+            header.parent_message_receipts.to_bytes(),
+            42,
+        )
+        .unwrap();
+        assert_eq!(receipt.gas_used, 42);
+
+        for secp in [false, true] {
+            let (p, is_secp, index) =
+                generate_message_proof(&store, &header.messages, &message(42, secp)).unwrap();
+            assert_eq!((is_secp, index), (secp, 42));
+            let cid = verify_block_message(p, header.messages.to_bytes(), secp, index).unwrap();
+            assert_eq!(cid, message(42, secp));
+        }
+        // the message is not in the block
+        assert!(generate_message_proof(&store, &header.messages, &message(100, true)).is_err());
+    }
+
+    #[test]
+    fn test_generated_proofs_layout() {
+        let bs = MemoryDB::default();
+        let (_, header) = decode_block_header(&block_header(&bs)).unwrap();
+        let store = ForestAdaptedBlockStorage::new(bs);
+
+        // the proofs of the actors end with the StateRoot the state root links to
+        let p =
+            generate_state_proof(&store, &header.parent_state_root, &Address::new_id(42)).unwrap();
+        assert_eq!(
+            cid::new_from_cbor(p.last().unwrap(), Blake2b256),
+            header.parent_state_root
+        );
+        let p = generate_receipt_proof(&store, &header.parent_message_receipts, 42).unwrap();
+        assert_eq!(
+            cid::new_from_cbor(p.last().unwrap(), Blake2b256),
+            header.parent_message_receipts
+        );
+        // the proofs of the messages end with the TxMeta the messages root links to
+        let (p, _, _) =
+            generate_message_proof(&store, &header.messages, &message(42, true)).unwrap();
+        assert_eq!(
+            cid::new_from_cbor(p.last().unwrap(), Blake2b256),
+            header.messages
+        );
+    }
+}
@@ -46,8 +46,9 @@ where
         })
     }
 
-    /// Generates the path of all node bytes from the root to the node that contains the
-    /// key. Returns Error::KeyNotFound if the key is not present in the tree.
+    /// Generates the path of all node bytes from the node that contains the key to the
+    /// root, as expected by `ProofVerify`. Returns Error::NotFound if the key is not
+    /// present in the tree.
     pub fn generate_proof(&self, k: &K) -> Result<Vec<Vec<u8>>, Error> {
         let mut path = Vec::new();
         if self.root.path_to_key(
@@ -57,9 +58,29 @@ where
             self.bit_width,
             self.store,
         )? {
+            path.reverse();
             Ok(path)
         } else {
             Err(Error::NotFound)
         }
     }
+
+    /// Generates the path of all node bytes from the node where the lookup of the key
+    /// ends, i.e. the node with an empty slot or a bucket without the key, to the root.
+    /// Returns Error::KeyExists if the key is present in the tree.
+    pub fn generate_exclusion_proof(&self, k: &K) -> Result<Vec<Vec<u8>>, Error> {
+        let mut path = Vec::new();
+        if self.root.path_to_key(
+            &mut HashAlgo::hash(k),
+            k,
+            &mut path,
+            self.bit_width,
+            self.store,
+        )? {
+            Err(Error::KeyExists)
+        } else {
+            path.reverse();
+            Ok(path)
+        }
+    }
 }
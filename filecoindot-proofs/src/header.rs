@@ -0,0 +1,124 @@
+// Copyright 2021 ChainSafe Systems
+// SPDX-License-Identifier: LGPL-3.0-only
+
+//! Decoding of the CBOR encoded filecoin block headers
+//!
+//! A block header is a CBOR tuple of 16 fields, only the fields linking the block
+//! to the chain and to its tries are decoded, the others are skipped.
+
+use crate::errors::Error;
+use cid::{Cid, Code::Blake2b256};
+use serde::de::{self, IgnoredAny, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+
+/// The position of the parents in the block header
+const PARENTS_FIELD: usize = 5;
+
+/// The fields of a filecoin block header read by the pallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeader {
+    /// The cids of the blocks of the parent tipset
+    pub parents: Vec<Cid>,
+    /// The epoch of the block
+    pub height: i64,
+    /// The root of the state tree computed from the parent tipset
+    pub parent_state_root: Cid,
+    /// The root of the receipts of the messages of the parent tipset
+    pub parent_message_receipts: Cid,
+    /// The root of the messages included in the block
+    pub messages: Cid,
+}
+
+impl<'de> Deserialize<'de> for BlockHeader {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct HeaderVisitor;
+
+        impl<'de> Visitor<'de> for HeaderVisitor {
+            type Value = BlockHeader;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a filecoin block header")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<BlockHeader, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                // miner, ticket, election proof, beacon entries and winning post proof
+                for i in 0..PARENTS_FIELD {
+                    seq.next_element::<IgnoredAny>()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                let parents = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(5, &self))?;
+                // parent weight
+                seq.next_element::<IgnoredAny>()?
+                    .ok_or_else(|| de::Error::invalid_length(6, &self))?;
+                let height = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(7, &self))?;
+                let parent_state_root = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(8, &self))?;
+                let parent_message_receipts = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(9, &self))?;
+                let messages = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(10, &self))?;
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+
+                Ok(BlockHeader {
+                    parents,
+                    height,
+                    parent_state_root,
+                    parent_message_receipts,
+                    messages,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(HeaderVisitor)
+    }
+}
+
+/// Decode the CBOR encoded block `header`, returns the cid of the block, i.e. the
+/// Blake2b-256 DAG-CBOR cid of the header, and the decoded header.
+pub fn decode_block_header(header: &[u8]) -> Result<(Cid, BlockHeader), Error> {
+    let decoded = serde_cbor::from_slice(header)?;
+    Ok((cid::new_from_cbor(header, Blake2b256), decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarking::block_header_generation;
+
+    #[test]
+    fn test_decode_block_header() {
+        let root = cid::new_from_cbor(&[1], Blake2b256);
+        let parent = cid::new_from_cbor(&[2], Blake2b256);
+        let header = block_header_generation(42, vec![parent.to_bytes()], root.to_bytes());
+
+        let (cid, decoded) = decode_block_header(&header).unwrap();
+        assert_eq!(cid, cid::new_from_cbor(&header, Blake2b256));
+        assert_eq!(
+            decoded,
+            BlockHeader {
+                parents: vec![parent],
+                height: 42,
+                parent_state_root: root,
+                parent_message_receipts: root,
+                messages: root,
+            }
+        );
+
+        // the header is truncated before its roots
+        assert!(decode_block_header(&serde_cbor::to_vec(&(0u8, 1u8)).unwrap()).is_err());
+    }
+}
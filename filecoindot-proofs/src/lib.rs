@@ -6,19 +6,30 @@
 mod errors;
 mod forest_hamt_adaptor;
 
+mod actors;
 mod amt;
 pub mod benchmarking;
 mod forest_amt_adaptor;
 mod generate;
 mod hamt;
+mod header;
+mod messages;
+mod state;
 mod traits;
 mod verify;
 
+pub use crate::actors::*;
 pub use crate::amt::Amt;
 pub use crate::forest_amt_adaptor::*;
 pub use crate::forest_hamt_adaptor::*;
+pub use crate::generate::*;
 pub use crate::hamt::Hamt;
-pub use crate::traits::{AMTNode, GetCid, HAMTNode, HashedBits, Verify};
+pub use crate::header::*;
+pub use crate::messages::*;
+pub use crate::state::*;
+pub use crate::traits::{
+    AMTNode, ChildLinks, GetCid, HAMTNode, HashAlgorithm, HashedBits, PathStep, Verify,
+};
 pub use crate::verify::*;
 pub use cid;
 pub use errors::Error;
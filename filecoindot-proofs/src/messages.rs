@@ -0,0 +1,198 @@
+// Copyright 2021 ChainSafe Systems
+// SPDX-License-Identifier: LGPL-3.0-only
+
+//! Proofs of the messages included in a filecoin block
+//!
+//! The messages root of a block header is not a trie on its own, it links to a
+//! `TxMeta`, i.e. the roots of the AMTs of the cids of the BLS and of the secp
+//! messages of the block. A message is proven by chaining:
+//!
+//! * an AMT proof of the message cid at its index in one of the AMTs
+//! * the raw `TxMeta` of the block, as the last item of the proof
+
+use crate::errors::Error;
+use crate::forest_amt_adaptor::ForestAmtAdaptedNode;
+use crate::traits::Verify;
+use crate::verify::ProofVerify;
+use cid::{Cid, Code::Blake2b256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The node of the AMTs of the message cids of a block
+pub type MessageCidNodeType = ForestAmtAdaptedNode<Cid>;
+
+/// The roots of the AMTs of the messages of a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxMeta {
+    /// The root of the AMT of the cids of the BLS messages
+    pub bls_messages: Cid,
+    /// The root of the AMT of the cids of the secp messages
+    pub secp_messages: Cid,
+}
+
+impl TxMeta {
+    /// The root of the AMT of the secp messages if `secp`, of the BLS messages otherwise
+    pub fn messages(&self, secp: bool) -> Cid {
+        if secp {
+            self.secp_messages
+        } else {
+            self.bls_messages
+        }
+    }
+}
+
+impl Serialize for TxMeta {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (&self.bls_messages, &self.secp_messages).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TxMeta {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (bls_messages, secp_messages) = Deserialize::deserialize(deserializer)?;
+        Ok(TxMeta {
+            bls_messages,
+            secp_messages,
+        })
+    }
+}
+
+/// Split a message proof into its AMT proof and the `TxMeta` of `messages_root`
+fn split_tx_meta(
+    mut proof: Vec<Vec<u8>>,
+    messages_root: &[u8],
+) -> Result<(Vec<Vec<u8>>, TxMeta), Error> {
+    let messages_root = crate::decode_cid(messages_root)?;
+    let tx_meta = proof.pop().ok_or(Error::VerificationFailed)?;
+    if cid::new_from_cbor(&tx_meta, Blake2b256) != messages_root {
+        return Err(Error::VerificationFailed);
+    }
+    let tx_meta = serde_cbor::from_slice(&tx_meta).map_err(|_| Error::VerificationFailed)?;
+    Ok((proof, tx_meta))
+}
+
+/// Verify the message at `index` of the secp messages if `secp`, of the BLS messages
+/// otherwise, of the block of `messages_root`. Returns the cid of the message.
+pub fn verify_block_message(
+    proof: Vec<Vec<u8>>,
+    messages_root: Vec<u8>,
+    secp: bool,
+    index: usize,
+) -> Result<Cid, Error> {
+    let (proof, tx_meta) = split_tx_meta(proof, &messages_root)?;
+    ProofVerify::verify_amt_value::<MessageCidNodeType>(
+        proof,
+        tx_meta.messages(secp).to_bytes(),
+        index,
+    )
+}
+
+/// Verify that there is no message at `index` of the secp messages if `secp`, of the
+/// BLS messages otherwise, of the block of `messages_root`.
+pub fn verify_block_message_exclusion(
+    proof: Vec<Vec<u8>>,
+    messages_root: Vec<u8>,
+    secp: bool,
+    index: usize,
+) -> Result<(), Error> {
+    let (proof, tx_meta) = split_tx_meta(proof, &messages_root)?;
+    ProofVerify::verify_amt_exclusion::<MessageCidNodeType>(
+        proof,
+        tx_meta.messages(secp).to_bytes(),
+        index,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_amt_exclusion_proof, generate_amt_proof, ForestAdaptedBlockStorage};
+    use ipld_amt::Amt as ForestAmt;
+    use ipld_blockstore::{BlockStore, MemoryDB};
+
+    #[test]
+    fn test_verify_block_message_works() {
+        let bs = MemoryDB::default();
+        let mut bls = ForestAmt::new(&bs);
+        let mut secp = ForestAmt::new(&bs);
+        for i in 0..100u64 {
+            bls.set(i as usize, cid::new_from_cbor(&i.to_be_bytes(), Blake2b256))
+                .unwrap();
+            secp.set(i as usize, cid::new_from_cbor(&[i as u8], Blake2b256))
+                .unwrap();
+        }
+        let tx_meta = TxMeta {
+            bls_messages: bls.flush().unwrap(),
+            secp_messages: secp.flush().unwrap(),
+        };
+        let messages_root = bs.put(&tx_meta, Blake2b256).unwrap();
+        let raw_tx_meta = serde_cbor::to_vec(&tx_meta).unwrap();
+
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let mut proof =
+            generate_amt_proof::<_, MessageCidNodeType>(&store, &tx_meta.secp_messages, 42)
+                .unwrap();
+        proof.push(raw_tx_meta);
+
+        let verify = |secp: bool, index: usize| {
+            verify_block_message(proof.clone(), messages_root.to_bytes(), secp, index)
+        };
+        assert_eq!(
+            verify(true, 42).unwrap(),
+            cid::new_from_cbor(&[42], Blake2b256)
+        );
+        // the proof is not a proof of the BLS messages
+        assert!(verify(false, 42).is_err());
+        // the proof does not lead to another message
+        assert!(verify(true, 43).is_err());
+        // the proof is not bound to another block
+        let other_root = cid::new_from_cbor(&[1, 2, 3], Blake2b256);
+        assert!(verify_block_message(proof.clone(), other_root.to_bytes(), true, 42).is_err());
+        // the proof is missing the TxMeta
+        let (_, amt_proof) = proof.split_last().unwrap();
+        assert!(
+            verify_block_message(amt_proof.to_vec(), messages_root.to_bytes(), true, 42).is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_block_message_exclusion_works() {
+        let bs = MemoryDB::default();
+        let mut bls = ForestAmt::new(&bs);
+        for i in 0..100u64 {
+            bls.set(i as usize, cid::new_from_cbor(&i.to_be_bytes(), Blake2b256))
+                .unwrap();
+        }
+        bls.delete(42).unwrap();
+        let tx_meta = TxMeta {
+            bls_messages: bls.flush().unwrap(),
+            secp_messages: ForestAmt::<Cid, _>::new(&bs).flush().unwrap(),
+        };
+        let messages_root = bs.put(&tx_meta, Blake2b256).unwrap();
+        let raw_tx_meta = serde_cbor::to_vec(&tx_meta).unwrap();
+
+        let store = ForestAdaptedBlockStorage::new(bs);
+        let mut proof = generate_amt_exclusion_proof::<_, MessageCidNodeType>(
+            &store,
+            &tx_meta.bls_messages,
+            42,
+        )
+        .unwrap();
+        proof.push(raw_tx_meta);
+
+        let verify = |index: usize| {
+            verify_block_message_exclusion(proof.clone(), messages_root.to_bytes(), false, index)
+        };
+        assert!(verify(42).is_ok());
+        // the message next to the removed one is in the block
+        assert!(verify(43).is_err());
+        // the proof is not bound to another block
+        let other_root = cid::new_from_cbor(&[1, 2, 3], Blake2b256);
+        assert!(verify_block_message_exclusion(proof, other_root.to_bytes(), false, 42).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2021 ChainSafe Systems
+// SPDX-License-Identifier: LGPL-3.0-only
+
+//! Proofs of the actors in the state tree of a filecoin block
+//!
+//! The parent state root of a block header is not a trie on its own, it links to a
+//! `StateRoot`, i.e. the version of the state tree and the roots of the HAMT of the
+//! actors and of the state info. An actor is proven by chaining:
+//!
+//! * a HAMT proof of the actor in the HAMT of the actors
+//! * the raw `StateRoot` of the block, as the last item of the proof
+
+use crate::errors::Error;
+use crate::forest_hamt_adaptor::{ForestAdaptedHashAlgo, StateNodeType};
+use crate::traits::Verify;
+use crate::verify::ProofVerify;
+use cid::{Cid, Code::Blake2b256};
+use forest_address::Address;
+use forest_vm::ActorState;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The bit width of the HAMT of the actors of the state tree
+pub const STATE_TREE_BIT_WIDTH: u8 = 5;
+
+/// The root of the state tree, as linked from the parent state root of a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateRoot {
+    /// The version of the state tree
+    pub version: u64,
+    /// The root of the HAMT of the actors
+    pub actors: Cid,
+    /// The cid of the state tree info
+    pub info: Cid,
+}
+
+impl Serialize for StateRoot {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (&self.version, &self.actors, &self.info).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StateRoot {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (version, actors, info) = Deserialize::deserialize(deserializer)?;
+        Ok(StateRoot {
+            version,
+            actors,
+            info,
+        })
+    }
+}
+
+/// Split a state proof into its HAMT proof and the `StateRoot` of `state_root`
+fn split_state_root(
+    mut proof: Vec<Vec<u8>>,
+    state_root: &[u8],
+) -> Result<(Vec<Vec<u8>>, StateRoot), Error> {
+    let state_root = crate::decode_cid(state_root)?;
+    let root = proof.pop().ok_or(Error::VerificationFailed)?;
+    if cid::new_from_cbor(&root, Blake2b256) != state_root {
+        return Err(Error::VerificationFailed);
+    }
+    let root = serde_cbor::from_slice(&root).map_err(|_| Error::VerificationFailed)?;
+    Ok((proof, root))
+}
+
+/// Verify the state of the actor `address` in the state tree of `state_root`. Returns
+/// the state of the actor.
+pub fn verify_actor_state(
+    proof: Vec<Vec<u8>>,
+    state_root: Vec<u8>,
+    address: &Address,
+) -> Result<ActorState, Error> {
+    let (proof, root) = split_state_root(proof, &state_root)?;
+    ProofVerify::verify_hamt_value::<Address, ActorState, StateNodeType, ForestAdaptedHashAlgo>(
+        proof,
+        root.actors.to_bytes(),
+        address,
+        STATE_TREE_BIT_WIDTH,
+    )
+}
+
+/// Verify that there is no actor `address` in the state tree of `state_root`.
+pub fn verify_actor_state_exclusion(
+    proof: Vec<Vec<u8>>,
+    state_root: Vec<u8>,
+    address: &Address,
+) -> Result<(), Error> {
+    let (proof, root) = split_state_root(proof, &state_root)?;
+    ProofVerify::verify_hamt_exclusion::<Address, ActorState, StateNodeType, ForestAdaptedHashAlgo>(
+        proof,
+        root.actors.to_bytes(),
+        address,
+        STATE_TREE_BIT_WIDTH,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_state_exclusion_proof, generate_state_proof, ForestAdaptedBlockStorage};
+    use forest_vm::TokenAmount;
+    use ipld_blockstore::{BlockStore, MemoryDB};
+    use ipld_hamt::Hamt as ForestHamt;
+
+    /// Store a state tree of 100 actors laid out as on mainnet, returns the state root
+    fn state_tree(bs: &MemoryDB) -> Cid {
+        let mut actors: ForestHamt<_, ActorState, Address> =
+            ForestHamt::new_with_bit_width(bs, STATE_TREE_BIT_WIDTH);
+        let code = cid::new_from_cbor(&[1], Blake2b256);
+        for i in 1..100 {
+            let state = ActorState::new(code, code, TokenAmount::from(i), i);
+            actors.set(Address::new_id(i), state).unwrap();
+        }
+        let root = StateRoot {
+            version: 4,
+            actors: actors.flush().unwrap(),
+            info: bs.put(&Vec::<()>::new(), Blake2b256).unwrap(),
+        };
+        bs.put(&root, Blake2b256).unwrap()
+    }
+
+    #[test]
+    fn test_verify_actor_state_works() {
+        let bs = MemoryDB::default();
+        let state_root = state_tree(&bs);
+        let store = ForestAdaptedBlockStorage::new(bs);
+
+        let address = Address::new_id(42);
+        let p = generate_state_proof(&store, &state_root, &address).unwrap();
+        let state = verify_actor_state(p.clone(), state_root.to_bytes(), &address).unwrap();
+        assert_eq!(state.sequence, 42);
+        assert_eq!(state.balance, TokenAmount::from(42));
+
+        // the proof is bound to the address
+        assert!(
+            verify_actor_state(p.clone(), state_root.to_bytes(), &Address::new_id(43)).is_err()
+        );
+        // the proof has to end with the state root of the block
+        let mut unwrapped = p.clone();
+        unwrapped.pop();
+        assert!(verify_actor_state(unwrapped, state_root.to_bytes(), &address).is_err());
+        // the HAMT of the actors cannot stand for the state root
+        let (proof, root) = split_state_root(p, &state_root.to_bytes()).unwrap();
+        assert!(verify_actor_state(proof, root.actors.to_bytes(), &address).is_err());
+    }
+
+    #[test]
+    fn test_verify_actor_state_exclusion_works() {
+        let bs = MemoryDB::default();
+        let state_root = state_tree(&bs);
+        let store = ForestAdaptedBlockStorage::new(bs);
+
+        let address = Address::new_id(1000);
+        let p = generate_state_exclusion_proof(&store, &state_root, &address).unwrap();
+        assert!(verify_actor_state_exclusion(p, state_root.to_bytes(), &address).is_ok());
+
+        // an actor of the state tree cannot be excluded
+        let address = Address::new_id(42);
+        assert!(generate_state_exclusion_proof(&store, &state_root, &address).is_err());
+        let p = generate_state_proof(&store, &state_root, &address).unwrap();
+        assert!(verify_actor_state_exclusion(p, state_root.to_bytes(), &address).is_err());
+    }
+}
@@ -21,6 +21,23 @@ pub trait GetCid {
     fn cid(&self) -> Result<Cid, Error>;
 }
 
+/// The step taken from a trie node when looking up a key
+#[derive(Debug, PartialEq, Eq)]
+pub enum PathStep<V> {
+    /// The key is stored under the linked child node
+    Link(Cid),
+    /// The key is stored in this node with the value
+    Found(V),
+    /// The key is not in the trie
+    NotFound,
+}
+
+/// Trie nodes linking to their children by cid
+pub trait ChildLinks {
+    /// The cids of all the children of this node
+    fn child_cids(&self) -> Vec<Cid>;
+}
+
 pub trait HAMTNode<K, V, H>: GetCid
 where
     K: Eq,
@@ -35,12 +52,26 @@ where
         store: &S,
     ) -> Result<bool, Error>;
 
+    /// Consume the next bits of `hash_bits` to find where `k` is stored from this node
+    fn next_step(&self, hash_bits: &mut H, k: &K, bit_width: u8) -> Result<PathStep<V>, Error>;
+
     fn get_by_cid<S: BlockStore>(&self, cid: &Cid, store: &S) -> Result<Option<Self>, Error>
     where
         Self: Sized;
 }
 
 pub trait AMTNode: GetCid {
+    /// The type of the values stored in the leaves
+    type Value;
+    /// The node as it is encoded, its links and values are laid out by the bit width
+    /// of its AMT once expanded
+    type Collapsed: DeserializeOwned;
+
+    /// Expand a decoded node of an AMT of `bit_width`
+    fn expand(node: Self::Collapsed, bit_width: usize) -> Result<Self, Error>
+    where
+        Self: Sized;
+
     fn path_to_key<S: BlockStore>(
         &self,
         store: &S,
@@ -50,6 +81,14 @@ pub trait AMTNode: GetCid {
         path: &mut Vec<Vec<u8>>,
     ) -> Result<bool, Error>;
 
+    /// Find where the index `i` is stored from this node at `height`
+    fn next_step(
+        &self,
+        bit_width: usize,
+        height: usize,
+        i: usize,
+    ) -> Result<PathStep<Self::Value>, Error>;
+
     fn get_by_cid<S: BlockStore>(
         &self,
         cid: &Cid,
@@ -64,11 +103,56 @@ pub trait AMTNode: GetCid {
 pub trait BlockStore {
     /// Get typed object from block store by Cid.
     fn get<T: DeserializeOwned>(&self, cid: &Cid) -> Result<T, Error>;
+
+    /// Get the raw bytes of an object from block store by Cid.
+    fn get_bytes(&self, cid: &Cid) -> Result<Vec<u8>, Error>;
 }
 
 /// The proof verification trait
 pub trait Verify {
     fn verify_proof<N>(proof: Vec<Vec<u8>>, node_cid: Vec<u8>) -> Result<(), Error>
     where
-        N: GetCid + for<'de> serde::Deserialize<'de>;
+        N: GetCid + ChildLinks + for<'de> serde::Deserialize<'de>;
+
+    /// Verify that `k` is in the HAMT of `root_cid` and return its value
+    fn verify_hamt_value<K, V, N, A>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<V, Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>;
+
+    /// Verify that the index `i` is in the AMT of `root_cid` and return its value
+    fn verify_amt_value<N>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        i: usize,
+    ) -> Result<N::Value, Error>
+    where
+        N: AMTNode;
+
+    /// Verify that `k` is not in the HAMT of `root_cid`
+    fn verify_hamt_exclusion<K, V, N, A>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<(), Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>;
+
+    /// Verify that the index `i` is not in the AMT of `root_cid`
+    fn verify_amt_exclusion<N>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        i: usize,
+    ) -> Result<(), Error>
+    where
+        N: AMTNode;
 }
@@ -1,22 +1,42 @@
 // Copyright 2021 ChainSafe Systems
 // SPDX-License-Identifier: LGPL-3.0-only
 
+use crate::amt::{nodes_for_height, MAX_BIT_WIDTH, MAX_HEIGHT};
 use crate::errors::Error;
-use crate::traits::{GetCid, Verify};
+use crate::traits::{AMTNode, ChildLinks, GetCid, HAMTNode, HashAlgorithm, PathStep, Verify};
 use crate::HAMTNodeType;
-use cid::Cid;
-use serde_cbor::de::from_slice;
+use cid::{Cid, Code::Blake2b256};
+use ipld_hamt::Hash;
+use serde::Serialize;
+use serde_cbor::{de::from_slice, to_vec};
 use std::convert::TryFrom;
 
 pub struct ProofVerify;
 
 impl ProofVerify {
-    fn traverse_and_match<N>(proof: &[Vec<u8>], index: usize, target_cid: &Cid) -> Result<(), Error>
+    /// Only backs the legacy `Verify::verify_proof`, the nodes are matched by cid
+    /// without following any key, use the `verify_*_path` walks to prove a value.
+    fn traverse_and_match<N>(
+        proof: &[Vec<u8>],
+        index: usize,
+        parent: Option<&N>,
+        target_cid: &Cid,
+    ) -> Result<(), Error>
     where
-        N: GetCid + for<'de> serde::Deserialize<'de>,
+        N: GetCid + ChildLinks + for<'de> serde::Deserialize<'de>,
     {
         let current_node: N = from_slice(&*proof[index]).map_err(|_| Error::VerificationFailed)?;
-        if current_node.cid()? == *target_cid {
+        let current_cid = current_node.cid()?;
+
+        // Apart from the root, each node has to be linked from the node before it,
+        // otherwise any node could be appended to the proof.
+        if let Some(parent) = parent {
+            if !parent.child_cids().contains(&current_cid) {
+                return Err(Error::VerificationFailed);
+            }
+        }
+
+        if current_cid == *target_cid {
             return Ok(());
         }
 
@@ -28,11 +48,176 @@ impl ProofVerify {
         }
 
         // now we search the previous index as we traverse deeper in to the trie
-        Self::traverse_and_match::<N>(proof, index - 1, target_cid)
+        Self::traverse_and_match::<N>(proof, index - 1, Some(&current_node), target_cid)
+    }
+
+    /// Walk a HAMT proof from the root down to the node storing `k`. Each node of the
+    /// proof has to be the child selected by the hash bits of `k`, and the last node
+    /// walked, i.e. `proof[0]`, has to contain `k`. Returns the value of `k`.
+    pub fn verify_hamt_path<K, V, N, A>(
+        proof: &[Vec<u8>],
+        root_cid: &Cid,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<V, Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>,
+    {
+        Self::walk_hamt::<K, V, N, A>(proof, root_cid, k, bit_width)?
+            .ok_or(Error::VerificationFailed)
+    }
+
+    /// Walk a HAMT proof from the root down to the node where the lookup of `k` ends.
+    /// The last node walked, i.e. `proof[0]`, has to have either an empty slot or a
+    /// bucket without `k` at the position of `k`.
+    pub fn verify_hamt_exclusion_path<K, V, N, A>(
+        proof: &[Vec<u8>],
+        root_cid: &Cid,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<(), Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>,
+    {
+        match Self::walk_hamt::<K, V, N, A>(proof, root_cid, k, bit_width)? {
+            Some(_) => Err(Error::VerificationFailed),
+            None => Ok(()),
+        }
+    }
+
+    /// Walk an AMT proof from the AMT root down to the leaf storing index `i`. Each
+    /// node of the proof has to be the child selected by `i` at its height, and the
+    /// leaf, i.e. `proof[0]`, has to contain a value at `i`. Returns the value at `i`.
+    pub fn verify_amt_path<N>(
+        proof: &[Vec<u8>],
+        root_cid: &Cid,
+        i: usize,
+    ) -> Result<N::Value, Error>
+    where
+        N: AMTNode,
+    {
+        Self::walk_amt::<N>(proof, root_cid, i)?.ok_or(Error::VerificationFailed)
+    }
+
+    /// Walk an AMT proof from the AMT root down to the node where the lookup of `i`
+    /// ends. The last node walked, i.e. `proof[0]`, has to have an empty slot at `i`,
+    /// unless `i` is out of the range of the AMT and the proof is the AMT root only.
+    pub fn verify_amt_exclusion_path<N>(
+        proof: &[Vec<u8>],
+        root_cid: &Cid,
+        i: usize,
+    ) -> Result<(), Error>
+    where
+        N: AMTNode,
+    {
+        match Self::walk_amt::<N>(proof, root_cid, i)? {
+            Some(_) => Err(Error::VerificationFailed),
+            None => Ok(()),
+        }
+    }
+
+    /// Walk a HAMT proof along the hash bits of `k`, returns the value of `k` if the
+    /// proof ends in the node storing it, or `None` if the proof ends where the lookup
+    /// of `k` fails.
+    fn walk_hamt<K, V, N, A>(
+        proof: &[Vec<u8>],
+        root_cid: &Cid,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<Option<V>, Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>,
+    {
+        let mut hash_bits = A::hash(k);
+        let mut expected_cid = *root_cid;
+        for (depth, raw_node) in proof.iter().rev().enumerate() {
+            if cid::new_from_cbor(raw_node, Blake2b256) != expected_cid {
+                return Err(Error::VerificationFailed);
+            }
+
+            // there should be no nodes left after the one ending the lookup
+            let last = depth == proof.len() - 1;
+            let node: N = from_slice(raw_node).map_err(|_| Error::VerificationFailed)?;
+            match node.next_step(&mut hash_bits, k, bit_width)? {
+                PathStep::Link(cid) if !last => expected_cid = cid,
+                PathStep::Found(value) if last => return Ok(Some(value)),
+                PathStep::NotFound if last => return Ok(None),
+                _ => return Err(Error::VerificationFailed),
+            }
+        }
+
+        // the proof is empty
+        Err(Error::VerificationFailed)
+    }
+
+    /// Walk an AMT proof along the index `i`, returns the value at `i` if the proof ends
+    /// in the leaf storing it, or `None` if the proof ends where the lookup of `i` fails.
+    fn walk_amt<N>(proof: &[Vec<u8>], root_cid: &Cid, i: usize) -> Result<Option<N::Value>, Error>
+    where
+        N: AMTNode,
+    {
+        Self::match_root(proof, root_cid)?;
+        let (root, nodes) = proof.split_last().ok_or(Error::VerificationFailed)?;
+        let (bit_width, mut height, _, node): (usize, usize, usize, N::Collapsed) =
+            from_slice(root).map_err(|_| Error::VerificationFailed)?;
+        if height > MAX_HEIGHT || bit_width == 0 || bit_width > MAX_BIT_WIDTH {
+            return Err(Error::VerificationFailed);
+        }
+        // all the nodes are laid out by the bit width of the AMT root
+        let mut node = N::expand(node, bit_width).map_err(|_| Error::VerificationFailed)?;
+
+        // the index is out of the range of the AMT, the AMT root is enough to prove it
+        if i >= nodes_for_height(bit_width, height + 1) {
+            if !nodes.is_empty() {
+                return Err(Error::VerificationFailed);
+            }
+            return Ok(None);
+        }
+
+        let mut i = i;
+        let mut nodes = nodes.iter().rev();
+        loop {
+            match node.next_step(bit_width, height, i)? {
+                PathStep::Link(cid) => {
+                    let raw_node = nodes.next().ok_or(Error::VerificationFailed)?;
+                    if height == 0 || cid::new_from_cbor(raw_node, Blake2b256) != cid {
+                        return Err(Error::VerificationFailed);
+                    }
+
+                    i %= nodes_for_height(bit_width, height);
+                    height -= 1;
+                    let raw_node = from_slice(raw_node).map_err(|_| Error::VerificationFailed)?;
+                    node = N::expand(raw_node, bit_width).map_err(|_| Error::VerificationFailed)?;
+                }
+                // there should be no nodes left after the one ending the lookup
+                PathStep::Found(value) if nodes.next().is_none() => return Ok(Some(value)),
+                PathStep::NotFound if nodes.next().is_none() => return Ok(None),
+                _ => return Err(Error::VerificationFailed),
+            }
+        }
+    }
+
+    /// Check that the last item of the proof, i.e. the root of the trie, hashes to `root_cid`.
+    fn match_root(proof: &[Vec<u8>], root_cid: &Cid) -> Result<(), Error> {
+        let root = proof.last().ok_or(Error::VerificationFailed)?;
+        if cid::new_from_cbor(root, Blake2b256) != *root_cid {
+            return Err(Error::VerificationFailed);
+        }
+        Ok(())
     }
 }
 
 impl Verify for ProofVerify {
+    /// Legacy check of the v1 host functions and of the rpc, the proof is neither bound
+    /// to a root nor to a key, see `ProofVerify::verify_hamt_path` and
+    /// `ProofVerify::verify_amt_path` instead.
+    ///
     /// Verify the proof and the the trie actually matches. Each cid in the proof
     /// is connected to its neighbours. The proof should match exactly in path from
     /// the root to the node.
@@ -40,13 +225,67 @@ impl Verify for ProofVerify {
     /// the head of the proof to be equal to node_cid, as long as it's in the proof.
     fn verify_proof<N>(proof: Vec<Vec<u8>>, node_cid: Vec<u8>) -> Result<(), Error>
     where
-        N: GetCid + for<'de> serde::Deserialize<'de>,
+        N: GetCid + ChildLinks + for<'de> serde::Deserialize<'de>,
     {
         let node_cid = Cid::try_from(node_cid).map_err(|_| Error::VerificationFailed)?;
         if proof.is_empty() {
             return Err(Error::VerificationFailed);
         }
-        Self::traverse_and_match::<N>(&proof, proof.len() - 1, &node_cid)
+        Self::traverse_and_match::<N>(&proof, proof.len() - 1, None, &node_cid)
+    }
+
+    fn verify_hamt_value<K, V, N, A>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<V, Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>,
+    {
+        let root_cid = decode_cid(&root_cid).map_err(|_| Error::VerificationFailed)?;
+        Self::verify_hamt_path::<K, V, N, A>(&proof, &root_cid, k, bit_width)
+    }
+
+    fn verify_amt_value<N>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        i: usize,
+    ) -> Result<N::Value, Error>
+    where
+        N: AMTNode,
+    {
+        let root_cid = decode_cid(&root_cid).map_err(|_| Error::VerificationFailed)?;
+        Self::verify_amt_path::<N>(&proof, &root_cid, i)
+    }
+
+    fn verify_hamt_exclusion<K, V, N, A>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        k: &K,
+        bit_width: u8,
+    ) -> Result<(), Error>
+    where
+        K: Eq + Hash,
+        A: HashAlgorithm,
+        N: HAMTNode<K, V, A::Output> + for<'de> serde::Deserialize<'de>,
+    {
+        let root_cid = decode_cid(&root_cid).map_err(|_| Error::VerificationFailed)?;
+        Self::verify_hamt_exclusion_path::<K, V, N, A>(&proof, &root_cid, k, bit_width)
+    }
+
+    fn verify_amt_exclusion<N>(
+        proof: Vec<Vec<u8>>,
+        root_cid: Vec<u8>,
+        i: usize,
+    ) -> Result<(), Error>
+    where
+        N: AMTNode,
+    {
+        let root_cid = decode_cid(&root_cid).map_err(|_| Error::VerificationFailed)?;
+        Self::verify_amt_exclusion_path::<N>(&proof, &root_cid, i)
     }
 }
 
@@ -56,3 +295,24 @@ impl Verify for ProofVerify {
 pub fn generic_verify(proof: Vec<Vec<u8>>, cid: &Cid) -> Result<(), Error> {
     ProofVerify::verify_proof::<HAMTNodeType>(proof, cid.to_bytes())
 }
+
+/// Replace the AMT root `(bit_width, height, count, node)` at the end of an AMT proof
+/// with its node, so that the proof only consists of nodes.
+pub fn unwrap_amt_root<N>(proof: &mut Vec<Vec<u8>>) -> Result<(), Error>
+where
+    N: AMTNode,
+    N::Collapsed: Serialize,
+{
+    let root = proof.pop().ok_or(Error::VerificationFailed)?;
+    // the node is kept as it is encoded, whatever the bit width of the AMT
+    let (_, _, _, node): (usize, usize, usize, N::Collapsed) =
+        from_slice(&root).map_err(|_| Error::VerificationFailed)?;
+    proof.push(to_vec(&node)?);
+    Ok(())
+}
+
+/// Decode a cid from its binary representation, the pallet stores the roots of the
+/// filecoin blocks as binary cids only.
+pub fn decode_cid(bytes: &[u8]) -> Result<Cid, Error> {
+    Cid::try_from(bytes.to_vec()).map_err(|e| Error::Other(e.to_string()))
+}
@@ -1,17 +1,23 @@
 // Copyright 2021 ChainSafe Systems
 // SPDX-License-Identifier: LGPL-3.0-only
 use cid::Cid;
-use filecoindot_proofs::{ForestAmtAdaptedNode, HAMTNodeType, ProofVerify, Verify};
+use filecoindot_proofs::{
+    unwrap_amt_root, ForestAmtAdaptedNode, HAMTNodeType, ProofVerify, Verify,
+};
 use jsonrpc_derive::rpc;
 use parking_lot::RwLock;
 use result::{Error, Result};
+use serde::Deserialize;
 use sp_core::{offchain::OffchainStorage, Decode, Encode};
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 use url::Url;
 
 /// filecoin rpc config
 pub const FILECOIN_RPC: &[u8] = b"FILECOIN_RPC";
 
+/// number of filecoin rpc endpoints required to agree
+pub const FILECOIN_RPC_QUORUM: &[u8] = b"FILECOIN_RPC_QUORUM";
+
 mod result;
 
 /// decode a hex String into a Vec of Vec of bytes
@@ -20,12 +26,66 @@ pub fn decode_proof_from_hex(hex: &str) -> Result<Vec<Vec<u8>>> {
     Ok(Decode::decode(&mut &*p)?)
 }
 
+/// filecoin rpc endpoint, either its url or its url with the headers to send
+/// along each request, encoded as the `Endpoint` type of `@chainsafe/filecoindot-types`,
+/// i.e. `{ "Url": .. }` or `{ "WithHeaders": { "url": .., "headers": .., "token": .. } }`.
+/// polkadot.js sends the variants in camel case.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum Endpoint {
+    /// endpoint without authentication
+    #[serde(alias = "url")]
+    Url(String),
+    /// endpoint requiring headers, `token` is sent as an `Authorization: Bearer` header
+    #[serde(alias = "withHeaders")]
+    WithHeaders {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        token: Option<String>,
+    },
+}
+
+impl Endpoint {
+    /// the url and the headers of the endpoint as stored in the offchain storage
+    fn config(&self) -> Result<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)> {
+        let (url, headers) = match self {
+            Endpoint::Url(url) => (url, vec![]),
+            Endpoint::WithHeaders {
+                url,
+                headers,
+                token,
+            } => {
+                if headers.keys().any(|name| name.is_empty()) {
+                    return Err(Error::InvalidEndpoint);
+                }
+                let mut headers = headers
+                    .iter()
+                    .map(|(name, value)| (name.as_bytes().to_vec(), value.as_bytes().to_vec()))
+                    .collect::<Vec<_>>();
+                if let Some(token) = token {
+                    headers.push((
+                        b"Authorization".to_vec(),
+                        format!("Bearer {}", token).into_bytes(),
+                    ));
+                }
+                (url, headers)
+            }
+        };
+        if !url.starts_with("http") || Url::parse(url).is_err() {
+            return Err(Error::InvalidEndpoint);
+        }
+
+        Ok((url.as_bytes().to_vec(), headers))
+    }
+}
+
 /// filecointdot rpc api
 #[rpc]
 pub trait FilecoindotApi {
-    /// set filecoin rpc endpoint for filecoindot
+    /// set filecoin rpc endpoint for filecoindot, the offchain worker only uses
+    /// the data returned identically by `quorum` of them, 1 by default
     #[rpc(name = "filecoindot_setRpcEndpoint")]
-    fn set_rpc_endpoint(&self, urls: Vec<String>) -> Result<()>;
+    fn set_rpc_endpoint(&self, endpoints: Vec<Endpoint>, quorum: Option<u32>) -> Result<()>;
 
     // verify receipt
     #[rpc(name = "filecoindot_verifyReceipt")]
@@ -55,35 +115,39 @@ impl<T> FilecoindotApi for Filecoindot<T>
 where
     T: OffchainStorage + 'static,
 {
-    fn set_rpc_endpoint(&self, urls: Vec<String>) -> Result<()> {
-        if urls.is_empty()
-            || urls
-                .iter()
-                .any(|url| !url.starts_with("http") || Url::parse(url).is_err())
-        {
+    fn set_rpc_endpoint(&self, endpoints: Vec<Endpoint>, quorum: Option<u32>) -> Result<()> {
+        if endpoints.is_empty() {
             return Err(Error::InvalidEndpoint);
         }
+        let configs = endpoints
+            .iter()
+            .map(Endpoint::config)
+            .collect::<Result<Vec<_>>>()?;
+
+        let quorum = quorum.unwrap_or(1);
+        if quorum == 0 || quorum as usize > endpoints.len() {
+            return Err(Error::InvalidQuorum);
+        }
 
-        self.storage.write().set(
+        let mut storage = self.storage.write();
+        storage.set(
             sp_offchain::STORAGE_PREFIX,
-            FILECOIN_RPC,
-            &urls
-                .iter()
-                .map(|url| url.as_bytes().to_vec())
-                .collect::<Vec<Vec<u8>>>()
-                .encode(),
+            FILECOIN_RPC_QUORUM,
+            &quorum.encode(),
         );
+        storage.set(sp_offchain::STORAGE_PREFIX, FILECOIN_RPC, &configs.encode());
         Ok(())
     }
 
     // verify receipt
     fn verify_receipt(&self, proof: String, cid: String) -> Result<bool> {
         let cid = Cid::try_from(&*cid)?;
-        Ok(ProofVerify::verify_proof::<ForestAmtAdaptedNode<String>>(
-            decode_proof_from_hex(&proof)?,
-            cid.to_bytes(),
-        )
-        .is_ok())
+        let mut proof = decode_proof_from_hex(&proof)?;
+        Ok(unwrap_amt_root::<ForestAmtAdaptedNode<String>>(&mut proof)
+            .and_then(|_| {
+                ProofVerify::verify_proof::<ForestAmtAdaptedNode<String>>(proof, cid.to_bytes())
+            })
+            .is_ok())
     }
 
     // verify state
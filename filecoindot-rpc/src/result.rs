@@ -9,6 +9,8 @@ pub type Result<T> = core::result::Result<T, Error>;
 pub enum Error {
     #[error("invalid filecoin rpc endpoint")]
     InvalidEndpoint,
+    #[error("quorum must be between 1 and the number of endpoints")]
+    InvalidQuorum,
     #[error("hex error: {0}")]
     FromHex(#[from] hex::FromHexError),
     #[error("codec error: {0}")]
@@ -3,16 +3,74 @@
 
 //! Benchmarking setup for filecoindot
 
-use crate::*;
-use filecoindot_io::benchmarking::{amt_proof_generation, hamt_proof_generation};
+use crate::{crypto::KEY_TYPE, *};
+use codec::Encode;
+use filecoindot_io::{
+    benchmarking::{
+        actor_state_exclusion_proof_generation, actor_state_proof_generation, amt_proof_generation,
+        block_header_generation, deal_proof_generation, message_exclusion_proof_generation,
+        message_proof_generation, message_receipt_exclusion_proof_generation,
+        message_receipt_proof_generation, sector_proof_generation,
+    },
+    forest_proof_verify,
+};
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, vec};
-use frame_support::traits::EnsureOrigin;
+use frame_support::{
+    dispatch::DispatchResult,
+    sp_io,
+    sp_runtime::traits::{IdentifyAccount, Saturating},
+    traits::{Currency, EnsureOrigin},
+};
 use frame_system::RawOrigin;
+use sp_core::sr25519;
+
+/// Block info with the roots all set to `root`
+fn block_info(root: Vec<u8>) -> VerifiedBlockInfo {
+    VerifiedBlockInfo {
+        epoch: 1,
+        parents: vec![vec![0, 0]],
+        message_root: root.clone(),
+        state_root: root.clone(),
+        receipts_root: root,
+    }
+}
+
+/// Set the checkpoint the blocks of `block_info` link to
+fn set_checkpoint<T: Config>() -> DispatchResult {
+    Pallet::<T>::set_checkpoint(
+        T::ManagerOrigin::successful_origin(),
+        vec![0, 0],
+        VerifiedBlockInfo {
+            epoch: 0,
+            parents: vec![],
+            ..block_info(vec![0, 0])
+        },
+    )
+}
+
+/// Endows `relayer` with enough balance to reserve the bond
+fn fund_relayer<T: Config>(relayer: &T::AccountId) {
+    let amount = T::Currency::minimum_balance().saturating_add(T::RelayerBond::get());
+    T::Currency::make_free_balance_be(relayer, amount);
+}
+
+/// Adds `relayer` to the relayers with its bond
+fn register_relayer<T: Config>(caller: T::Origin, relayer: T::AccountId) -> DispatchResult {
+    fund_relayer::<T>(&relayer);
+    Pallet::<T>::add_relayer(caller, relayer)
+}
 
 benchmarks! {
+    where_clause {
+        where
+            T::Public: From<sr25519::Public>,
+            T::Signature: From<sr25519::Signature>,
+    }
+
     add_relayer {
         let caller = T::ManagerOrigin::successful_origin();
         let relayer: T::AccountId = account("relayer", 0, 0);
+        fund_relayer::<T>(&relayer);
     }: {
         Pallet::<T>::add_relayer(caller, relayer.clone())?;
     } verify {
@@ -23,7 +81,7 @@ benchmarks! {
         let caller = T::ManagerOrigin::successful_origin();
         let relayer: T::AccountId = account("relayer", 0, 0);
 
-        Pallet::<T>::add_relayer(caller.clone(), relayer.clone())?;
+        register_relayer::<T>(caller.clone(), relayer.clone())?;
     }: {
         Pallet::<T>::remove_relayer(caller, relayer.clone())?;
     } verify {
@@ -37,24 +95,240 @@ benchmarks! {
         assert_eq!(VoteThreshold::<T>::get(), 2);
     }
 
+    claim_rewards {
+        let relayer: T::AccountId = account("relayer", 0, 0);
+        fund_relayer::<T>(&relayer);
+        PendingRewards::<T>::insert(&relayer, T::RelayerReward::get());
+    }: {
+        Pallet::<T>::claim_rewards(RawOrigin::Signed(relayer.clone()).into())?;
+    } verify {
+        assert!(!PendingRewards::<T>::contains_key(&relayer));
+    }
+
     submit_block_vote {
         let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
         let relayer: T::AccountId = account("relayer", 0, 0);
 
-        Pallet::<T>::add_relayer(caller.clone(), relayer.clone())?;
+        register_relayer::<T>(caller.clone(), relayer.clone())?;
         Pallet::<T>::set_vote_threshold(caller, 1)?;
     }: {
-        Pallet::<T>::submit_block_vote(RawOrigin::Signed(relayer).into(), vec![0], vec![0])?;
+        Pallet::<T>::submit_block_vote(RawOrigin::Signed(relayer).into(), vec![0], block_info(vec![0]), 1 + T::ConfirmationDepth::get())?;
     } verify {
         assert!(!BlockSubmissionProposals::<T>::contains_key(&vec![0]));
     }
 
+    submit_block_header {
+        let caller = T::ManagerOrigin::successful_origin();
+        let relayer: T::AccountId = account("relayer", 0, 0);
+
+        register_relayer::<T>(caller.clone(), relayer.clone())?;
+        Pallet::<T>::set_vote_threshold(caller.clone(), 1)?;
+
+        let (_, root, _) = amt_proof_generation(1);
+        Pallet::<T>::set_checkpoint(
+            caller.clone(),
+            root.clone(),
+            VerifiedBlockInfo {
+                epoch: 0,
+                parents: vec![],
+                ..block_info(root.clone())
+            },
+        )?;
+        let header = block_header_generation(1, vec![root.clone()], root);
+        let (block_cid, _) = Pallet::<T>::decode_block_header(header.clone())?;
+    }: {
+        Pallet::<T>::submit_block_header(RawOrigin::Signed(relayer).into(), header, 1 + T::ConfirmationDepth::get())?;
+    } verify {
+        assert!(VerifiedBlocks::<T>::contains_key(&block_cid));
+    }
+
+    submit_tipset_vote {
+        let n in 1 .. 10;
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let relayer: T::AccountId = account("relayer", 0, 0);
+
+        register_relayer::<T>(caller.clone(), relayer.clone())?;
+        Pallet::<T>::set_vote_threshold(caller, 1)?;
+
+        let tipset_key = (0..n as u8).map(|i| vec![1, i]).collect::<Vec<_>>();
+        let roots = (0..n as u8)
+            .map(|i| BlockRoots {
+                message_root: vec![i],
+                state_root: vec![0],
+                receipts_root: vec![0],
+            })
+            .collect::<Vec<_>>();
+    }: {
+        Pallet::<T>::submit_tipset_vote(
+            RawOrigin::Signed(relayer).into(),
+            tipset_key.clone(),
+            1,
+            vec![vec![0, 0]],
+            roots,
+            1 + T::ConfirmationDepth::get()
+        )?;
+    } verify {
+        assert!(tipset_key.iter().all(|block_cid| VerifiedBlocks::<T>::contains_key(block_cid)));
+    }
+
+    submit_aggregated_votes {
+        let n in 1 .. 10;
+        let r in 1 .. 10;
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let submitter: T::AccountId = account("submitter", 0, 0);
+
+        let tipset_key = (0..n as u8).map(|i| vec![1, i]).collect::<Vec<_>>();
+        let roots = (0..n as u8)
+            .map(|i| BlockRoots {
+                message_root: vec![i],
+                state_root: vec![0],
+                receipts_root: vec![0],
+            })
+            .collect::<Vec<_>>();
+
+        let mut signatures = vec![];
+        for _ in 0..r {
+            let public = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+            let payload = TipsetVotePayload {
+                tipset_key: tipset_key.clone(),
+                height: 1,
+                parents: vec![vec![0, 0]],
+                roots: roots.clone(),
+                head: 1 + T::ConfirmationDepth::get(),
+                public: T::Public::from(public),
+            };
+            let signature = sp_io::crypto::sr25519_sign(KEY_TYPE, &public, &payload.encode())
+                .ok_or("cannot sign the vote")?;
+
+            register_relayer::<T>(caller.clone(), payload.public.clone().into_account())?;
+            signatures.push((payload.public, T::Signature::from(signature)));
+        }
+        Pallet::<T>::set_vote_threshold(caller, r)?;
+    }: {
+        Pallet::<T>::submit_aggregated_votes(
+            RawOrigin::Signed(submitter).into(),
+            tipset_key.clone(),
+            1,
+            vec![vec![0, 0]],
+            roots,
+            1 + T::ConfirmationDepth::get(),
+            signatures
+        )?;
+    } verify {
+        assert!(tipset_key.iter().all(|block_cid| VerifiedBlocks::<T>::contains_key(block_cid)));
+    }
+
+    close_tipset_proposal {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let relayer: T::AccountId = account("relayer", 0, 0);
+
+        register_relayer::<T>(caller.clone(), relayer.clone())?;
+        let roots = BlockRoots {
+            message_root: vec![0],
+            state_root: vec![0],
+            receipts_root: vec![0],
+        };
+        Pallet::<T>::submit_tipset_vote(
+            RawOrigin::Signed(relayer).into(),
+            vec![vec![1, 0]],
+            1,
+            vec![vec![0, 0]],
+            vec![roots],
+            1 + T::ConfirmationDepth::get()
+        )?;
+
+        let now = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(now + VotingPeriod::<T>::get() + 1u32.into());
+    }: {
+        Pallet::<T>::close_tipset_proposal(caller, vec![vec![1, 0]])?;
+    } verify {
+        assert!(!TipsetSubmissionProposals::<T>::contains_key(vec![vec![1, 0]]));
+    }
+
+    settle_votes {
+        let v in 1 .. T::MaxRelayers::get();
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+
+        // all the voters but the first one voted for other info than the approved info
+        // and are slashed, the costliest settlement
+        let mut relayers = Relayers::<T>::iter_keys().collect::<Vec<_>>();
+        for i in relayers.len() as u32..v {
+            let relayer: T::AccountId = account("relayer", i, 0);
+            register_relayer::<T>(caller.clone(), relayer.clone())?;
+            relayers.push(relayer);
+        }
+        Pallet::<T>::set_vote_threshold(caller, 1)?;
+
+        let approved = block_info(vec![0]);
+        for (i, relayer) in relayers.into_iter().take(v as usize).enumerate() {
+            let info = if i == 0 { approved.clone() } else { block_info(vec![1]) };
+            BlockProposalVotes::<T>::insert(vec![0], relayer, info);
+        }
+        MessageRootCidCounter::<T>::insert(vec![0], &approved, v);
+    }: {
+        Pallet::<T>::finalize_block(vec![0]);
+    } verify {
+        assert!(VerifiedBlocks::<T>::contains_key(&vec![0]));
+    }
+
+    set_checkpoint {
+        let caller = T::ManagerOrigin::successful_origin();
+        let info = VerifiedBlockInfo {
+            epoch: 0,
+            parents: vec![],
+            ..block_info(vec![0])
+        };
+    }: {
+        Pallet::<T>::set_checkpoint(caller, vec![0], info)?;
+    } verify {
+        assert!(VerifiedBlocks::<T>::contains_key(&vec![0]));
+    }
+
+    verify_ancestry {
+        let n in 1 .. 100;
+        let caller = T::ManagerOrigin::successful_origin();
+        let (_, root, _) = amt_proof_generation(1);
+
+        // a chain of `n` headers from the oldest, linked to the checkpoint on top of them
+        let mut headers = vec![];
+        let mut parent = root.clone();
+        for epoch in 1..=n as i64 {
+            let header = block_header_generation(epoch, vec![parent], root.clone());
+            let (block_cid, _) = Pallet::<T>::decode_block_header(header.clone())?;
+            headers.push(header);
+            parent = block_cid;
+        }
+        headers.reverse();
+        let oldest = Pallet::<T>::decode_block_header(headers[headers.len() - 1].clone())?.0;
+
+        Pallet::<T>::set_checkpoint(
+            caller,
+            vec![0],
+            VerifiedBlockInfo {
+                epoch: n as i64 + 1,
+                parents: vec![parent],
+                ..block_info(root)
+            },
+        )?;
+        let alice: T::AccountId = account("alice", 0, 0);
+    }: {
+        Pallet::<T>::verify_ancestry(RawOrigin::Signed(alice).into(), vec![0], headers)?;
+    } verify {
+        assert!(VerifiedBlocks::<T>::contains_key(&oldest));
+    }
+
     close_block_proposal {
         let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
         let relayer: T::AccountId = account("relayer", 0, 0);
 
-        Pallet::<T>::add_relayer(caller.clone(), relayer.clone())?;
-        Pallet::<T>::submit_block_vote(RawOrigin::Signed(relayer).into(), vec![0], vec![0])?;
+        register_relayer::<T>(caller.clone(), relayer.clone())?;
+        Pallet::<T>::submit_block_vote(RawOrigin::Signed(relayer).into(), vec![0], block_info(vec![0]), 1 + T::ConfirmationDepth::get())?;
 
         let now = frame_system::Pallet::<T>::block_number();
         frame_system::Pallet::<T>::set_block_number(now + VotingPeriod::<T>::get() + 1u32.into());
@@ -69,103 +343,504 @@ benchmarks! {
 
     verify_receipt {
         let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
         let alice: T::AccountId = account("alice", 0, 0);
         let bob: T::AccountId = account("bob", 1, 1);
         let charlie: T::AccountId = account("charlie", 2, 2);
 
-        Pallet::<T>::add_relayer(caller.clone(), alice.clone())?;
-        Pallet::<T>::add_relayer(caller.clone(), bob.clone())?;
-        Pallet::<T>::add_relayer(caller, charlie.clone())?;
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
 
         let block_cid = vec![0, 1];
-        let message_cid = vec![0, 1];
+        let (proof, root) = message_receipt_proof_generation(100);
+        let roots = block_info(root);
 
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(alice.clone()).into(),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(bob).into(),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(charlie).into(),
             block_cid.clone(),
-            message_cid
+            roots,
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
-        let (proof, cid) = amt_proof_generation(100);
     }: {
-        Pallet::<T>::verify_receipt(RawOrigin::Signed(alice).into(), proof, block_cid, cid)?;
+        Pallet::<T>::verify_receipt(RawOrigin::Signed(alice).into(), proof, block_cid, 100)?;
     }
 
     verify_state {
         let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
         let alice: T::AccountId = account("alice", 0, 0);
         let bob: T::AccountId = account("bob", 1, 1);
         let charlie: T::AccountId = account("charlie", 2, 2);
 
-        Pallet::<T>::add_relayer(caller.clone(), alice.clone())?;
-        Pallet::<T>::add_relayer(caller.clone(), bob.clone())?;
-        Pallet::<T>::add_relayer(caller, charlie.clone())?;
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
 
         let block_cid = vec![0, 1];
-        let message_cid = vec![0, 1];
+        let (proof, root, address) = actor_state_proof_generation(500);
+        let roots = block_info(root);
 
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(alice.clone()).into(),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(bob).into(),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(charlie).into(),
             block_cid.clone(),
-            message_cid
+            roots,
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
-
-        let (proof, cid) = hamt_proof_generation();
     }: {
-        Pallet::<T>::verify_state(RawOrigin::Signed(alice).into(), proof, block_cid, cid)?;
+        Pallet::<T>::verify_state(RawOrigin::Signed(alice).into(), proof, block_cid, address)?;
     }
 
     verify_message {
         let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root, cid) = message_proof_generation(500);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_message(RawOrigin::Signed(alice).into(), proof, block_cid, true, 500, cid)?;
+    }
+
+    verify_actor_state {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root, address) = actor_state_proof_generation(500);
+        let (code, head, nonce, balance) = forest_proof_verify::verify_actor_state(
+            proof.clone(),
+            root.clone(),
+            address.clone()
+        ).unwrap();
+        let state = ActorState { code, head, nonce, balance };
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_actor_state(RawOrigin::Signed(alice).into(), proof, block_cid, address, state)?;
+    }
+
+    verify_deal {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (state_proof, market_state, deal_proof, root) = deal_proof_generation(42);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_deal(
+            RawOrigin::Signed(alice).into(),
+            block_cid,
+            42,
+            state_proof,
+            market_state,
+            deal_proof
+        )?;
+    }
+
+    verify_sector {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
         let alice: T::AccountId = account("alice", 0, 0);
         let bob: T::AccountId = account("bob", 1, 1);
         let charlie: T::AccountId = account("charlie", 2, 2);
 
-        Pallet::<T>::add_relayer(caller.clone(), alice.clone())?;
-        Pallet::<T>::add_relayer(caller.clone(), bob.clone())?;
-        Pallet::<T>::add_relayer(caller, charlie.clone())?;
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
 
         let block_cid = vec![0, 1];
-        let message_cid = vec![0, 1];
+        let (miner, state_proof, miner_state, sector_proof, root) = sector_proof_generation(42);
+        let roots = block_info(root);
 
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(alice.clone()).into(),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(bob).into(),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
         Pallet::<T>::submit_block_vote(
             RawOrigin::Signed(charlie).into(),
             block_cid.clone(),
-            message_cid
+            roots,
+            1 + T::ConfirmationDepth::get()
         ).unwrap();
+    }: {
+        Pallet::<T>::verify_sector(
+            RawOrigin::Signed(alice).into(),
+            block_cid,
+            miner,
+            42,
+            state_proof,
+            miner_state,
+            sector_proof
+        )?;
+    }
 
-        let (proof, cid) = hamt_proof_generation();
+    verify_message_receipt {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root) = message_receipt_proof_generation(100);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_message_receipt(RawOrigin::Signed(alice).into(), proof, block_cid, 100)?;
+    }
+
+    verify_receipt_value {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root) = message_receipt_proof_generation(100);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_receipt_value(RawOrigin::Signed(alice).into(), proof, block_cid, 100)?;
+    }
+
+    verify_state_value {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root, address) = actor_state_proof_generation(500);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_state_value(RawOrigin::Signed(alice).into(), proof, block_cid, address)?;
+    }
+
+    verify_message_value {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root, _) = message_proof_generation(500);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_message_value(RawOrigin::Signed(alice).into(), proof, block_cid, true, 500)?;
+    }
+
+    verify_receipt_exclusion {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root) = message_receipt_exclusion_proof_generation(100);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_receipt_exclusion(RawOrigin::Signed(alice).into(), proof, block_cid, 100)?;
+    }
+
+    verify_state_exclusion {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root, address) = actor_state_exclusion_proof_generation(1001);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+    }: {
+        Pallet::<T>::verify_state_exclusion(RawOrigin::Signed(alice).into(), proof, block_cid, address)?;
+    }
+
+    verify_message_exclusion {
+        let caller = T::ManagerOrigin::successful_origin();
+        set_checkpoint::<T>()?;
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 1, 1);
+        let charlie: T::AccountId = account("charlie", 2, 2);
+
+        register_relayer::<T>(caller.clone(), alice.clone())?;
+        register_relayer::<T>(caller.clone(), bob.clone())?;
+        register_relayer::<T>(caller, charlie.clone())?;
+
+        let block_cid = vec![0, 1];
+        let (proof, root) = message_exclusion_proof_generation(500);
+        let roots = block_info(root);
+
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(alice.clone()).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(bob).into(),
+            block_cid.clone(),
+            roots.clone(),
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
+        Pallet::<T>::submit_block_vote(
+            RawOrigin::Signed(charlie).into(),
+            block_cid.clone(),
+            roots,
+            1 + T::ConfirmationDepth::get()
+        ).unwrap();
     }: {
-        Pallet::<T>::verify_message(RawOrigin::Signed(alice).into(), proof, block_cid, cid)?;
+        Pallet::<T>::verify_message_exclusion(RawOrigin::Signed(alice).into(), proof, block_cid, true, 500)?;
     }
 }
 
@@ -5,17 +5,34 @@
 //!
 //! This pallet uses a set of AccountIds to identify who
 //! can vote on proposals. Relayers may be added, removed.
-//! There is no bound on how many members may exist in the committee.
+//! There are at most `MaxRelayers` members in the committee, which bounds the votes
+//! settled when a proposal is resolved.
 //!
 //! For each block addition proposal, relayers can vote on them.
 //! The pallet will lazily resolve all the proposals.
 //! Admin could also resolve manually.
 //!
+//! Each relayer reserves a bond when added to the set, the bond of a relayer who
+//! voted for other info than the approved info of a block or a tipset is slashed and
+//! the relayer is removed from the set, unless it would leave less relayers than the
+//! vote threshold. The relayers who voted for the approved info
+//! are rewarded, the rewards are claimed later on. The votes for a block dropped as a
+//! fork are neither slashed nor rewarded.
+//!
+//! The verified blocks form a chain starting from a checkpoint set by the admin, a
+//! block can only be voted on once all its parents are verified. When a block
+//! conflicts with an already verified block of the same height, i.e. they do not
+//! share the same parents, the block verified first is kept and a fork is reported.
+//!
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use self::{
     crypto::{FilecoindotId, KEY_TYPE},
     pallet::*,
+    types::{
+        ActorState, BlockRoots, MessageReceipt, SectorOnChainInfo, StorageDeal, TipsetVotePayload,
+        VerifiedBlockInfo,
+    },
 };
 
 mod crypto;
@@ -33,21 +50,37 @@ pub mod pallet {
         log,
         pallet_prelude::*,
         sp_runtime::{
-            traits::{Saturating, ValidateUnsigned},
-            transaction_validity::InvalidTransaction,
+            traits::{IdentifyAccount, Saturating, UniqueSaturatedInto, ValidateUnsigned, Zero},
+            transaction_validity::{InvalidTransaction, ValidTransaction},
         },
         sp_std::prelude::*,
+        traits::{Currency, Imbalance, OnUnbalanced, ReservableCurrency},
+        transactional,
     };
     use frame_system::{
-        offchain::{AppCrypto, CreateSignedTransaction},
+        offchain::{AppCrypto, CreateSignedTransaction, SignedPayload},
         pallet_prelude::*,
     };
 
-    use crate::types::{BlockSubmissionProposal, ProposalStatus};
+    use crate::types::{
+        ActorState, BlockRoots, BlockSubmissionProposal, MessageReceipt, ProposalStatus,
+        SectorOnChainInfo, StorageDeal, TipsetVotePayload, VerifiedBlockInfo,
+    };
     use filecoindot_io::forest_proof_verify;
 
     pub(crate) const DEFAULT_VOTE_THRESHOLD: u32 = 1;
 
+    pub(crate) type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    pub(crate) type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
+
+    pub(crate) type PositiveImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::PositiveImbalance;
+
     pub(crate) type BlockSubmissionProposalOf<T> = BlockSubmissionProposal<
         <T as frame_system::Config>::AccountId,
         <T as frame_system::Config>::BlockNumber,
@@ -59,6 +92,9 @@ pub mod pallet {
     // TODO: clarify the exact type, too many clones
     pub(crate) type MessageRootCid = Vec<u8>;
 
+    /// The cids of all the blocks of a tipset
+    pub(crate) type TipsetKey = Vec<BlockCid>;
+
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
@@ -72,6 +108,25 @@ pub mod pallet {
         type WeightInfo: WeightInfo;
         /// The timeout of the http requests of ocw in milliseconds
         type OffchainWorkerTimeout: Get<u64>;
+        /// The number of filecoin epochs a tipset must be buried under before the
+        /// relayers can vote on it, filecoin reaches finality after 900 epochs
+        #[pallet::constant]
+        type ConfirmationDepth: Get<i64>;
+        /// The currency the bonds of the relayers are reserved in
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// The bond reserved from a relayer when it is added to the set
+        #[pallet::constant]
+        type RelayerBond: Get<BalanceOf<Self>>;
+        /// Handler for the bonds slashed from the relayers
+        type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+        /// The reward credited to each relayer who voted for the approved info of a block
+        #[pallet::constant]
+        type RelayerReward: Get<BalanceOf<Self>>;
+        /// Handler for the rewards minted when claimed by the relayers
+        type Reward: OnUnbalanced<PositiveImbalanceOf<Self>>;
+        /// The maximum number of relayers in the set, and of the votes on a proposal
+        #[pallet::constant]
+        type MaxRelayers: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -83,6 +138,16 @@ pub mod pallet {
     pub(crate) type Relayers<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
 
+    /// Track the bond reserved from each relayer
+    #[pallet::storage]
+    pub(crate) type RelayerBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+    /// Track the rewards each relayer can claim
+    #[pallet::storage]
+    pub(crate) type PendingRewards<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
     /// Count the total number of relayers
     #[pallet::storage]
     pub(super) type RelayerCount<T: Config> = StorageValue<_, u32, ValueQuery>;
@@ -92,7 +157,8 @@ pub mod pallet {
     pub(crate) type BlockSubmissionProposals<T: Config> =
         StorageMap<_, Blake2_128Concat, BlockCid, BlockSubmissionProposalOf<T>, OptionQuery>;
 
-    /// Track the accounts which voted for a particular submitted block proposal
+    /// Track the accounts which voted for a particular submitted block proposal and
+    /// the info they voted for
     #[pallet::storage]
     pub(crate) type BlockProposalVotes<T: Config> = StorageDoubleMap<
         _,
@@ -100,26 +166,67 @@ pub mod pallet {
         BlockCid,
         Blake2_128Concat,
         T::AccountId,
-        (),
+        VerifiedBlockInfo,
         OptionQuery,
     >;
 
-    /// Track the message root cid votes for block cid
+    /// Track the message root cid votes for block cid, the message root is voted
+    /// together with the epoch, the parents and the state and receipts roots of the block
     #[pallet::storage]
     pub(crate) type MessageRootCidCounter<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
         BlockCid,
         Blake2_128Concat,
-        MessageRootCid,
+        VerifiedBlockInfo,
+        u32,
+        OptionQuery,
+    >;
+
+    /// Track the tipset submission related proposals stored
+    #[pallet::storage]
+    pub(crate) type TipsetSubmissionProposals<T: Config> =
+        StorageMap<_, Blake2_128Concat, TipsetKey, BlockSubmissionProposalOf<T>, OptionQuery>;
+
+    /// Track the accounts which voted for a particular submitted tipset proposal and
+    /// the info of the blocks they voted for
+    #[pallet::storage]
+    pub(crate) type TipsetProposalVotes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        TipsetKey,
+        Blake2_128Concat,
+        T::AccountId,
+        Vec<VerifiedBlockInfo>,
+        OptionQuery,
+    >;
+
+    /// Track the votes for tipset key, the info of all the blocks of the tipset are
+    /// voted together
+    #[pallet::storage]
+    pub(crate) type TipsetInfoCounter<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        TipsetKey,
+        Blake2_128Concat,
+        Vec<VerifiedBlockInfo>,
         u32,
         OptionQuery,
     >;
 
-    /// Track the blocks that have been verified
+    /// Track the blocks that have been verified with the info agreed by the relayers
     #[pallet::storage]
     pub(crate) type VerifiedBlocks<T: Config> =
-        StorageMap<_, Blake2_128Concat, BlockCid, (), OptionQuery>;
+        StorageMap<_, Blake2_128Concat, BlockCid, VerifiedBlockInfo, OptionQuery>;
+
+    /// Track the verified blocks by their filecoin epoch
+    #[pallet::storage]
+    pub(crate) type VerifiedBlocksByHeight<T: Config> =
+        StorageMap<_, Twox64Concat, i64, Vec<BlockCid>, ValueQuery>;
+
+    /// The block trusted by the admin that the verified chain starts from
+    #[pallet::storage]
+    pub(crate) type Checkpoint<T: Config> = StorageValue<_, BlockCid, OptionQuery>;
 
     /// The threshold of votes required for a proposal to be qualified for approval resolution
     #[pallet::storage]
@@ -138,6 +245,12 @@ pub mod pallet {
         /// Relayer removed from set
         /// \[AccountId\]
         RelayerRemoved(T::AccountId),
+        /// The bond of the relayer is slashed for voting against the approved info
+        /// \[AccountId, Balance\]
+        RelayerSlashed(T::AccountId, BalanceOf<T>),
+        /// The rewards of the relayer are claimed
+        /// \[AccountId, Balance\]
+        RewardsClaimed(T::AccountId, BalanceOf<T>),
         /// Relayer threshold updated to value
         /// \[RelayerThreshold\]
         VoteThresholdChanged(u32),
@@ -156,6 +269,58 @@ pub mod pallet {
         /// Proposal created
         /// \[BlockCid\]
         ProposalCreated(BlockCid),
+        /// Vote for the tipset proposal casted
+        /// \[TipsetKey, AccountId\]
+        TipsetVoteCasted(TipsetKey, T::AccountId),
+        /// The tipset proposal is approved
+        /// \[TipsetKey\]
+        TipsetApproved(TipsetKey),
+        /// The tipset proposal is rejected
+        /// \[TipsetKey\]
+        TipsetRejected(TipsetKey),
+        /// Tipset proposal created
+        /// \[TipsetKey\]
+        TipsetProposalCreated(TipsetKey),
+        /// The checkpoint of the verified chain is set
+        /// \[BlockCid, Epoch\]
+        CheckpointSet(BlockCid, i64),
+        /// The approved block conflicts with the verified block of the same epoch, the
+        /// approved block is dropped
+        /// \[Epoch, VerifiedBlockCid, DroppedBlockCid\]
+        ForkDetected(i64, BlockCid, BlockCid),
+        /// The ancestors of a verified block are verified from their headers
+        /// \[BlockCid, AncestorBlockCid\]
+        AncestryVerified(BlockCid, BlockCid),
+        /// The state of an actor is verified
+        /// \[BlockCid, Address, Balance, Nonce\]
+        ActorStateVerified(BlockCid, Vec<u8>, u128, u64),
+        /// The deal is verified in the storage market actor
+        /// \[BlockCid, DealId, StorageDeal\]
+        DealVerified(BlockCid, u64, StorageDeal),
+        /// The sector is verified in the sectors of the miner actor
+        /// \[BlockCid, Miner, SectorOnChainInfo\]
+        SectorVerified(BlockCid, Vec<u8>, SectorOnChainInfo),
+        /// The message receipt is verified in the receipts of the block
+        /// \[BlockCid, Index, MessageReceipt\]
+        MessageReceiptVerified(BlockCid, u64, MessageReceipt),
+        /// The CBOR encoded message receipt is verified in the receipts of the block
+        /// \[BlockCid, Index, Value\]
+        ReceiptValueVerified(BlockCid, u64, Vec<u8>),
+        /// The CBOR encoded state of an actor is verified in the state of the block
+        /// \[BlockCid, Address, Value\]
+        StateValueVerified(BlockCid, Vec<u8>, Vec<u8>),
+        /// The cid of a message is verified in the secp or BLS messages of the block
+        /// \[BlockCid, Secp, Index, MessageCid\]
+        MessageValueVerified(BlockCid, bool, u64, Vec<u8>),
+        /// There is no message receipt at the index of the receipts of the block
+        /// \[BlockCid, Index\]
+        ReceiptExclusionVerified(BlockCid, u64),
+        /// There is no actor at the address in the state of the block
+        /// \[BlockCid, Address\]
+        StateExclusionVerified(BlockCid, Vec<u8>),
+        /// There is no message at the index of the secp or BLS messages of the block
+        /// \[BlockCid, Secp, Index\]
+        MessageExclusionVerified(BlockCid, bool, u64),
     }
 
     // Errors inform users that something went wrong.
@@ -169,6 +334,10 @@ pub mod pallet {
         NotRelayer,
         /// Not enough relayers
         NotEnoughRelayer,
+        /// The relayer set is full
+        TooManyRelayers,
+        /// The proposal has reached the maximum number of votes
+        TooManyVotes,
         /// Proposal has already completed
         ProposalCompleted,
         /// Proposal has already expired
@@ -181,6 +350,28 @@ pub mod pallet {
         BlockAlreadyVerified,
         /// Cannot verify the proof provided
         VerificationError,
+        /// Cannot decode the block header provided
+        InvalidBlockHeader,
+        /// The signatures of the votes are missing or invalid
+        InvalidSignature,
+        /// The tipset or the block is not buried under enough epochs yet
+        TipsetNotConfirmed,
+        /// The parents of the block are not verified
+        UnknownParent,
+        /// The epoch of the block is not after the epoch of its parents
+        InvalidEpoch,
+        /// The headers do not link the block to its ancestor
+        InvalidAncestry,
+        /// An ancestor conflicts with the verified block of the same epoch
+        AncestorForked,
+        /// The tipset key is empty or does not match the roots of the blocks
+        InvalidTipset,
+        /// The relayer cannot reserve the bond
+        InsufficientBond,
+        /// The account has no rewards to claim
+        NoRewards,
+        /// The rewards are below the existential deposit of the account to create
+        RewardsBelowExistentialDeposit,
     }
 
     #[pallet::hooks]
@@ -220,11 +411,34 @@ pub mod pallet {
         /// By default unsigned transactions are disallowed, but implementing the validator
         /// here we make sure that some particular calls (the ones produced by offchain worker)
         /// are being whitelisted and marked as valid.
-        fn validate_unsigned(
-            _source: TransactionSource,
-            _call: &Self::Call,
-        ) -> TransactionValidity {
-            InvalidTransaction::Call.into()
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let (payload, signature) = match call {
+                Call::submit_tipset_vote_unsigned { payload, signature } => (payload, signature),
+                _ => return InvalidTransaction::Call.into(),
+            };
+
+            if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                return InvalidTransaction::BadProof.into();
+            }
+            let who = payload.public.clone().into_account();
+            if !Self::is_relayer(&who) {
+                return InvalidTransaction::BadSigner.into();
+            }
+            if TipsetProposalVotes::<T>::contains_key(&payload.tipset_key, &who)
+                || payload
+                    .tipset_key
+                    .iter()
+                    .any(|block_cid| VerifiedBlocks::<T>::contains_key(block_cid))
+            {
+                return InvalidTransaction::Stale.into();
+            }
+
+            // one vote of each relayer for each tipset, until the end of the voting period
+            ValidTransaction::with_tag_prefix("Filecoindot")
+                .and_provides((who, payload.tipset_key.clone()))
+                .longevity(VotingPeriod::<T>::get().unique_saturated_into())
+                .propagate(true)
+                .build()
         }
     }
 
@@ -234,8 +448,22 @@ pub mod pallet {
             VoteThreshold::<T>::put(self.vote_threshold);
             VotingPeriod::<T>::put(self.vote_period);
             for r in self.relayers.clone() {
-                // should not fail in this case
-                Pallet::<T>::register_relayer(r).unwrap();
+                // the bonds are reserved from the balances endowed at genesis
+                assert!(
+                    T::Currency::can_reserve(&r, T::RelayerBond::get()),
+                    "genesis relayer {:?} can not reserve the relayer bond",
+                    r,
+                );
+                assert!(
+                    !Pallet::<T>::is_relayer(&r),
+                    "duplicate genesis relayer {:?}",
+                    r,
+                );
+                assert!(
+                    RelayerCount::<T>::get() < T::MaxRelayers::get(),
+                    "more genesis relayers than MaxRelayers",
+                );
+                Pallet::<T>::register_relayer(r).expect("the relayer is validated above");
             }
         }
     }
@@ -271,67 +499,72 @@ pub mod pallet {
             Ok(())
         }
 
-        // ************** Proposal Lifecycle *************
-        /// Commits a vote in favour of the provided block cid and message root.
-        #[pallet::weight(T::WeightInfo::submit_block_vote())]
-        pub fn submit_block_vote(
-            origin: OriginFor<T>,
-            block_cid: BlockCid,
-            message_root_cid: MessageRootCid,
-        ) -> DispatchResult {
+        /// Pays out the rewards credited to the caller for its votes
+        #[pallet::weight(T::WeightInfo::claim_rewards())]
+        pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            ensure!(Self::is_relayer(&who), Error::<T>::NotRelayer);
+            let rewards = PendingRewards::<T>::get(&who);
+            ensure!(!rewards.is_zero(), Error::<T>::NoRewards);
+
+            // nothing is minted for a new account below the existential deposit, the
+            // rewards are kept until they are enough to create it
+            let imbalance = T::Currency::deposit_creating(&who, rewards);
             ensure!(
-                !VerifiedBlocks::<T>::contains_key(block_cid.clone()),
-                Error::<T>::BlockAlreadyVerified
+                !imbalance.peek().is_zero(),
+                Error::<T>::RewardsBelowExistentialDeposit
             );
+            PendingRewards::<T>::remove(&who);
+            T::Reward::on_unbalanced(imbalance);
 
-            BlockSubmissionProposals::<T>::try_mutate(
-                block_cid.clone(),
-                |maybe_proposal| -> Result<(), DispatchError> {
-                    let proposal = maybe_proposal.get_or_insert_with(|| {
-                        let start_block: T::BlockNumber = frame_system::Pallet::<T>::block_number();
-                        let end_block = start_block.saturating_add(VotingPeriod::<T>::get());
-                        let r = BlockSubmissionProposal::new(who.clone(), start_block, end_block);
-                        Self::deposit_event(Event::ProposalCreated(block_cid.clone()));
-                        r
-                    });
+            Self::deposit_event(Event::RewardsClaimed(who, rewards));
+            Ok(())
+        }
 
-                    match Self::vote_block_proposal(
-                        block_cid.clone(),
-                        message_root_cid,
-                        proposal,
-                        who.clone(),
-                    ) {
-                        Ok(()) => {
-                            Self::deposit_event(Event::VoteCasted(block_cid.clone(), who));
-                            if Self::try_resolve_proposal(block_cid, proposal) {
-                                *maybe_proposal = None;
-                            }
-                            Ok(())
-                        }
-                        Err(e) => match e {
-                            // Resolution is performed lazily, if it happens to be expired,
-                            // we will issue resolution command.
-                            Error::<T>::ProposalExpired => {
-                                if Self::try_resolve_proposal(block_cid, proposal) {
-                                    *maybe_proposal = None;
-                                }
-                                Err(e.into())
-                            }
-                            e => Err(e.into()),
-                        },
-                    }
-                },
-            )?;
+        // ************** Proposal Lifecycle *************
+        /// Commits a vote in favour of the provided block cid and its info.
+        ///
+        /// `head` is the height of the chain head seen by the relayer, the block must be
+        /// at least `ConfirmationDepth` epochs below it. The pallet has no view of the
+        /// filecoin chain head, so the depth check is advisory only: a relayer can
+        /// report any head, the block is confirmed as far as `VoteThreshold` relayers
+        /// report honest heads.
+        #[pallet::weight(T::WeightInfo::submit_block_vote()
+            .saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        pub fn submit_block_vote(
+            origin: OriginFor<T>,
+            block_cid: BlockCid,
+            info: VerifiedBlockInfo,
+            head: i64,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let settled = Self::vote_block(who, block_cid, info, head)?;
+            Self::settled_weight(T::WeightInfo::submit_block_vote(), settled)
+        }
 
-            Ok(())
+        /// Commits a vote in favour of the CBOR encoded filecoin block `header`, the
+        /// block cid and its info are derived from the header. `head` is the same as
+        /// in `submit_block_vote`.
+        #[pallet::weight(T::WeightInfo::submit_block_header()
+            .saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        pub fn submit_block_header(
+            origin: OriginFor<T>,
+            header: Vec<u8>,
+            head: i64,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let (block_cid, info) = Self::decode_block_header(header)?;
+            let settled = Self::vote_block(who, block_cid, info, head)?;
+            Self::settled_weight(T::WeightInfo::submit_block_header(), settled)
         }
 
         /// Admin can close the proposal when it has expired. The admin ought to have called this
         /// when the proposal expired, otherwise it
-        #[pallet::weight(T::WeightInfo::close_block_proposal())]
-        pub fn close_block_proposal(origin: OriginFor<T>, block_cid: BlockCid) -> DispatchResult {
+        #[pallet::weight(T::WeightInfo::close_block_proposal()
+            .saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        pub fn close_block_proposal(
+            origin: OriginFor<T>,
+            block_cid: BlockCid,
+        ) -> DispatchResultWithPostInfo {
             Self::ensure_admin(origin)?;
             ensure!(
                 !VerifiedBlocks::<T>::contains_key(block_cid.clone()),
@@ -343,59 +576,489 @@ pub mod pallet {
 
             let now = frame_system::Pallet::<T>::block_number();
             let threshold = VoteThreshold::<T>::get();
-            Self::resolve_proposal(&mut p, &block_cid, &now, threshold)?;
+            let counts = MessageRootCidCounter::<T>::iter_prefix_values(&block_cid);
+            Self::resolve_proposal(&mut p, counts, &now, threshold)?;
 
-            Self::try_resolve_proposal(block_cid, &p);
+            let settled = Self::try_resolve_proposal(block_cid, &p).unwrap_or(0);
+            Self::settled_weight(T::WeightInfo::close_block_proposal(), settled)
+        }
 
+        /// Commits a vote in favour of the tipset `tipset_key` at `height`, on top of the
+        /// tipset `parents`, with the roots of each of its blocks in the order of the key.
+        /// The blocks of the tipset are approved or rejected together.
+        ///
+        /// `head` is the height of the chain head seen by the relayer, the tipset must be
+        /// at least `ConfirmationDepth` epochs below it. As in `submit_block_vote`, the
+        /// depth check is advisory only: the head is not checked against the filecoin
+        /// chain, the tipset is confirmed as far as `VoteThreshold` relayers report
+        /// honest heads.
+        #[pallet::weight(T::WeightInfo::submit_tipset_vote(tipset_key.len() as u32)
+            .saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        pub fn submit_tipset_vote(
+            origin: OriginFor<T>,
+            tipset_key: TipsetKey,
+            height: i64,
+            parents: TipsetKey,
+            roots: Vec<BlockRoots>,
+            head: i64,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let weight = T::WeightInfo::submit_tipset_vote(tipset_key.len() as u32);
+            let settled = Self::vote_tipset(who, tipset_key, height, parents, roots, head)?;
+            Self::settled_weight(weight, settled)
+        }
+
+        /// Same as `submit_tipset_vote` without fees, the vote is sent in an unsigned
+        /// transaction with the `payload` signed by the `FilecoindotId` key of the
+        /// relayer, see `validate_unsigned`.
+        #[pallet::weight(T::WeightInfo::submit_tipset_vote(payload.tipset_key.len() as u32)
+            .saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        pub fn submit_tipset_vote_unsigned(
+            origin: OriginFor<T>,
+            payload: TipsetVotePayload<T::Public>,
+            _signature: T::Signature,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            let weight = T::WeightInfo::submit_tipset_vote(payload.tipset_key.len() as u32);
+            let TipsetVotePayload {
+                tipset_key,
+                height,
+                parents,
+                roots,
+                head,
+                public,
+            } = payload;
+            let settled = Self::vote_tipset(
+                public.into_account(),
+                tipset_key,
+                height,
+                parents,
+                roots,
+                head,
+            )?;
+            Self::settled_weight(weight, settled)
+        }
+
+        /// Commits the votes of several relayers for the tipset `tipset_key` at once, the
+        /// votes are signed offchain by the `FilecoindotId` key of each relayer, i.e. the
+        /// signatures of the `TipsetVotePayload` of the vote with the key of the relayer.
+        /// The votes are counted until the tipset is resolved, none of them is counted
+        /// if any of them is rejected.
+        #[pallet::weight(T::WeightInfo::submit_aggregated_votes(
+            tipset_key.len() as u32,
+            signatures.len() as u32,
+        ).saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        #[transactional]
+        pub fn submit_aggregated_votes(
+            origin: OriginFor<T>,
+            tipset_key: TipsetKey,
+            height: i64,
+            parents: TipsetKey,
+            roots: Vec<BlockRoots>,
+            head: i64,
+            signatures: Vec<(T::Public, T::Signature)>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            ensure!(!signatures.is_empty(), Error::<T>::InvalidSignature);
+            let weight = T::WeightInfo::submit_aggregated_votes(
+                tipset_key.len() as u32,
+                signatures.len() as u32,
+            );
+
+            // check all the votes before counting them
+            let mut voted_key = tipset_key.clone();
+            Self::normalize_cids(&mut voted_key);
+            let mut relayers: Vec<T::AccountId> = Vec::with_capacity(signatures.len());
+            for (public, signature) in signatures {
+                let payload = TipsetVotePayload {
+                    tipset_key: tipset_key.clone(),
+                    height,
+                    parents: parents.clone(),
+                    roots: roots.clone(),
+                    head,
+                    public: public.clone(),
+                };
+                ensure!(
+                    SignedPayload::<T>::verify::<T::AuthorityId>(&payload, signature),
+                    Error::<T>::InvalidSignature
+                );
+
+                let who = public.into_account();
+                ensure!(Self::is_relayer(&who), Error::<T>::NotRelayer);
+                ensure!(
+                    !relayers.contains(&who)
+                        && !TipsetProposalVotes::<T>::contains_key(&voted_key, &who),
+                    Error::<T>::AlreadyVoted
+                );
+                relayers.push(who);
+            }
+
+            let mut settled = 0;
+            for who in relayers {
+                settled = Self::vote_tipset(
+                    who,
+                    tipset_key.clone(),
+                    height,
+                    parents.clone(),
+                    roots.clone(),
+                    head,
+                )?;
+                // the remaining votes would be rejected once the tipset is resolved
+                if !TipsetSubmissionProposals::<T>::contains_key(&voted_key) {
+                    break;
+                }
+            }
+
+            Self::settled_weight(weight, settled)
+        }
+
+        /// Admin can close the tipset proposal when it has expired, same as
+        /// `close_block_proposal`
+        #[pallet::weight(T::WeightInfo::close_tipset_proposal()
+            .saturating_add(T::WeightInfo::settle_votes(T::MaxRelayers::get())))]
+        pub fn close_tipset_proposal(
+            origin: OriginFor<T>,
+            tipset_key: TipsetKey,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_admin(origin)?;
+
+            let mut p = TipsetSubmissionProposals::<T>::get(&tipset_key)
+                .ok_or(Error::<T>::ProposalNotExists)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let threshold = VoteThreshold::<T>::get();
+            let counts = TipsetInfoCounter::<T>::iter_prefix_values(&tipset_key);
+            Self::resolve_proposal(&mut p, counts, &now, threshold)?;
+
+            let settled = Self::try_resolve_tipset_proposal(tipset_key, &p).unwrap_or(0);
+            Self::settled_weight(T::WeightInfo::close_tipset_proposal(), settled)
+        }
+
+        /// Set the block the verified chain starts from, the block is verified with
+        /// `info` without any vote and without checking its parents
+        #[pallet::weight(T::WeightInfo::set_checkpoint())]
+        pub fn set_checkpoint(
+            origin: OriginFor<T>,
+            block_cid: BlockCid,
+            info: VerifiedBlockInfo,
+        ) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            ensure!(
+                !VerifiedBlocks::<T>::contains_key(&block_cid),
+                Error::<T>::BlockAlreadyVerified
+            );
+
+            let epoch = info.epoch;
+            VerifiedBlocksByHeight::<T>::append(epoch, block_cid.clone());
+            VerifiedBlocks::<T>::insert(&block_cid, info);
+            Checkpoint::<T>::put(&block_cid);
+
+            Self::deposit_event(Event::CheckpointSet(block_cid, epoch));
+            Ok(())
+        }
+
+        /// Verify the ancestors of the verified `block_cid` from the CBOR encoded
+        /// `headers`, starting from a parent of `block_cid` and each header being a
+        /// parent of the previous one. All the ancestors are verified without any vote.
+        #[pallet::weight(T::WeightInfo::verify_ancestry(headers.len() as u32))]
+        pub fn verify_ancestry(
+            origin: OriginFor<T>,
+            block_cid: BlockCid,
+            headers: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let ancestor = Self::verify_ancestry_inner(block_cid.clone(), headers)?;
+            Self::deposit_event(Event::AncestryVerified(block_cid, ancestor));
             Ok(())
         }
 
-        /// Verify the receipt of the filecoin
+        /// Verify the receipt at `index` of the filecoin
         #[pallet::weight(T::WeightInfo::verify_receipt())]
         pub fn verify_receipt(
             origin: OriginFor<T>,
             proof: Vec<Vec<u8>>,
             block_cid: BlockCid,
-            cid: Vec<u8>,
+            index: u64,
         ) -> DispatchResult {
             ensure_signed(origin)?;
-            Self::verify_receipt_inner(proof, block_cid, cid)
+            Self::verify_receipt_inner(proof, block_cid, index)
         }
 
-        /// Verify the state of the filecoin
+        /// Verify the state of the actor at `address` of the filecoin
         #[pallet::weight(T::WeightInfo::verify_state())]
         pub fn verify_state(
             origin: OriginFor<T>,
             proof: Vec<Vec<u8>>,
             block_cid: BlockCid,
-            cid: Vec<u8>,
+            address: Vec<u8>,
         ) -> DispatchResult {
             ensure_signed(origin)?;
-            Self::verify_state_inner(proof, block_cid, cid)
+            Self::verify_state_inner(proof, block_cid, address)
         }
 
-        /// Verify the state of the filecoin
+        /// Verify that the message `cid` is at `index` of the secp messages if `secp`,
+        /// of the BLS messages otherwise, of the filecoin
         #[pallet::weight(T::WeightInfo::verify_message())]
         pub fn verify_message(
             origin: OriginFor<T>,
             proof: Vec<Vec<u8>>,
             block_cid: BlockCid,
+            secp: bool,
+            index: u64,
             cid: Vec<u8>,
         ) -> DispatchResult {
             ensure_signed(origin)?;
-            Self::verify_message_inner(proof, block_cid, cid)
+            Self::verify_message_inner(proof, block_cid, secp, index, cid)
+        }
+
+        /// Verify the state of the actor at `address` of the filecoin
+        #[pallet::weight(T::WeightInfo::verify_actor_state())]
+        pub fn verify_actor_state(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            address: Vec<u8>,
+            state: ActorState,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::verify_actor_state_inner(proof, block_cid.clone(), address.clone(), &state)?;
+            Self::deposit_event(Event::ActorStateVerified(
+                block_cid,
+                address,
+                state.balance,
+                state.nonce,
+            ));
+            Ok(())
+        }
+
+        /// Verify the deal `deal_id` in the storage market actor of the filecoin
+        #[pallet::weight(T::WeightInfo::verify_deal())]
+        pub fn verify_deal(
+            origin: OriginFor<T>,
+            block_cid: BlockCid,
+            deal_id: u64,
+            state_proof: Vec<Vec<u8>>,
+            market_state: Vec<u8>,
+            deal_proof: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let deal = Self::verify_deal_inner(
+                block_cid.clone(),
+                deal_id,
+                state_proof,
+                market_state,
+                deal_proof,
+            )?;
+            Self::deposit_event(Event::DealVerified(block_cid, deal_id, deal));
+            Ok(())
+        }
+
+        /// Verify the sector `sector_number` of the miner actor at `miner` of the filecoin
+        #[pallet::weight(T::WeightInfo::verify_sector())]
+        pub fn verify_sector(
+            origin: OriginFor<T>,
+            block_cid: BlockCid,
+            miner: Vec<u8>,
+            sector_number: u64,
+            state_proof: Vec<Vec<u8>>,
+            miner_state: Vec<u8>,
+            sector_proof: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let sector = Self::verify_sector_inner(
+                block_cid.clone(),
+                miner.clone(),
+                sector_number,
+                state_proof,
+                miner_state,
+                sector_proof,
+            )?;
+            Self::deposit_event(Event::SectorVerified(block_cid, miner, sector));
+            Ok(())
+        }
+
+        /// Verify the message receipt at `index` of the filecoin and emit its exit code,
+        /// return data and gas used
+        #[pallet::weight(T::WeightInfo::verify_message_receipt())]
+        pub fn verify_message_receipt(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            index: u64,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let receipt = Self::verify_message_receipt_inner(proof, block_cid.clone(), index)?;
+            Self::deposit_event(Event::MessageReceiptVerified(block_cid, index, receipt));
+            Ok(())
+        }
+
+        /// Verify the message receipt at `index` of the filecoin and emit it CBOR encoded
+        #[pallet::weight(T::WeightInfo::verify_receipt_value())]
+        pub fn verify_receipt_value(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            index: u64,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let value = Self::verify_receipt_value_inner(proof, block_cid.clone(), index)?;
+            Self::deposit_event(Event::ReceiptValueVerified(block_cid, index, value));
+            Ok(())
+        }
+
+        /// Verify the state of the actor at `address` of the filecoin and emit it CBOR
+        /// encoded
+        #[pallet::weight(T::WeightInfo::verify_state_value())]
+        pub fn verify_state_value(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            address: Vec<u8>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let value = Self::verify_state_value_inner(proof, block_cid.clone(), address.clone())?;
+            Self::deposit_event(Event::StateValueVerified(block_cid, address, value));
+            Ok(())
+        }
+
+        /// Verify the message at `index` of the secp messages if `secp`, of the BLS
+        /// messages otherwise, of the filecoin and emit its cid
+        #[pallet::weight(T::WeightInfo::verify_message_value())]
+        pub fn verify_message_value(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            secp: bool,
+            index: u64,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let cid = Self::verify_message_value_inner(proof, block_cid.clone(), secp, index)?;
+            Self::deposit_event(Event::MessageValueVerified(block_cid, secp, index, cid));
+            Ok(())
+        }
+
+        /// Verify that there is no message receipt at `index` of the filecoin
+        #[pallet::weight(T::WeightInfo::verify_receipt_exclusion())]
+        pub fn verify_receipt_exclusion(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            index: u64,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::verify_receipt_exclusion_inner(proof, block_cid.clone(), index)?;
+            Self::deposit_event(Event::ReceiptExclusionVerified(block_cid, index));
+            Ok(())
+        }
+
+        /// Verify that there is no actor at `address` in the state of the filecoin
+        #[pallet::weight(T::WeightInfo::verify_state_exclusion())]
+        pub fn verify_state_exclusion(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            address: Vec<u8>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::verify_state_exclusion_inner(proof, block_cid.clone(), address.clone())?;
+            Self::deposit_event(Event::StateExclusionVerified(block_cid, address));
+            Ok(())
+        }
+
+        /// Verify that there is no message at `index` of the secp messages if `secp`, of
+        /// the BLS messages otherwise, of the filecoin
+        #[pallet::weight(T::WeightInfo::verify_message_exclusion())]
+        pub fn verify_message_exclusion(
+            origin: OriginFor<T>,
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            secp: bool,
+            index: u64,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::verify_message_exclusion_inner(proof, block_cid.clone(), secp, index)?;
+            Self::deposit_event(Event::MessageExclusionVerified(block_cid, secp, index));
+            Ok(())
         }
     }
 
     impl<T: Config> Pallet<T> {
+        /// Returns the info of `block_cid` if the block has been verified
+        pub fn verified_block(block_cid: &[u8]) -> Option<VerifiedBlockInfo> {
+            VerifiedBlocks::<T>::get(block_cid)
+        }
+
+        /// Returns the cids of the verified blocks at the filecoin `epoch`
+        pub fn verified_blocks_at_height(epoch: i64) -> Vec<BlockCid> {
+            VerifiedBlocksByHeight::<T>::get(epoch)
+        }
+
+        /// Returns the cid and the info of the CBOR encoded block `header`
+        pub fn decode_block_header(
+            header: Vec<u8>,
+        ) -> Result<(BlockCid, VerifiedBlockInfo), DispatchError> {
+            let (block_cid, epoch, parents, message_root, state_root, receipts_root) =
+                forest_proof_verify::decode_block_header(header)
+                    .ok_or(Error::<T>::InvalidBlockHeader)?;
+            Ok((
+                block_cid,
+                VerifiedBlockInfo {
+                    epoch,
+                    parents,
+                    message_root,
+                    state_root,
+                    receipts_root,
+                },
+            ))
+        }
+
+        /// Verify the ancestors of `block_cid` linked by `headers` and returns the
+        /// cid of the oldest one
+        pub fn verify_ancestry_inner(
+            block_cid: BlockCid,
+            headers: Vec<Vec<u8>>,
+        ) -> Result<BlockCid, DispatchError> {
+            ensure!(!headers.is_empty(), Error::<T>::InvalidAncestry);
+            let mut child =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+
+            // check every link before verifying any of the ancestors
+            let mut ancestors = Vec::with_capacity(headers.len());
+            for header in headers {
+                let (cid, info) = Self::decode_block_header(header)?;
+                ensure!(
+                    child.parents.contains(&cid) && info.epoch < child.epoch,
+                    Error::<T>::InvalidAncestry
+                );
+                // the ancestors stay on the chain of the verified blocks
+                ensure!(
+                    VerifiedBlocks::<T>::contains_key(&cid)
+                        || Self::conflicting_block(&info).is_none(),
+                    Error::<T>::AncestorForked
+                );
+                child = info.clone();
+                ancestors.push((cid, info));
+            }
+
+            let mut ancestor = block_cid;
+            for (cid, info) in ancestors {
+                if !VerifiedBlocks::<T>::contains_key(&cid) {
+                    VerifiedBlocksByHeight::<T>::append(info.epoch, cid.clone());
+                    VerifiedBlocks::<T>::insert(&cid, info);
+                }
+                ancestor = cid;
+            }
+            Ok(ancestor)
+        }
+
         pub fn verify_receipt_inner(
             proof: Vec<Vec<u8>>,
             block_cid: BlockCid,
-            cid: Vec<u8>,
+            index: u64,
         ) -> DispatchResult {
-            forest_proof_verify::verify_receipt(proof, cid).ok_or(Error::<T>::VerificationError)?;
-            Self::verified_block(&block_cid)
-                .then(|| ())
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            forest_proof_verify::verify_receipt(proof, block.receipts_root, index)
                 .ok_or(Error::<T>::VerificationError)?;
             Ok(())
         }
@@ -403,30 +1066,220 @@ pub mod pallet {
         pub fn verify_message_inner(
             proof: Vec<Vec<u8>>,
             block_cid: BlockCid,
+            secp: bool,
+            index: u64,
             cid: Vec<u8>,
         ) -> DispatchResult {
-            forest_proof_verify::verify_message(proof, cid).ok_or(Error::<T>::VerificationError)?;
-            Self::verified_block(&block_cid)
-                .then(|| ())
-                .ok_or(Error::<T>::VerificationError)?;
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            let proven =
+                forest_proof_verify::verify_message(proof, block.message_root, secp, index)
+                    .ok_or(Error::<T>::VerificationError)?;
+            ensure!(proven == cid, Error::<T>::VerificationError);
             Ok(())
         }
 
         pub fn verify_state_inner(
             proof: Vec<Vec<u8>>,
             block_cid: BlockCid,
-            cid: Vec<u8>,
+            address: Vec<u8>,
+        ) -> DispatchResult {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            forest_proof_verify::verify_state(proof, block.state_root, address)
+                .ok_or(Error::<T>::VerificationError)?;
+            Ok(())
+        }
+
+        /// Verify that the actor at `address` has `state` in the state of `block_cid`
+        pub fn verify_actor_state_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            address: Vec<u8>,
+            state: &ActorState,
+        ) -> DispatchResult {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            let (code, head, nonce, balance) =
+                forest_proof_verify::verify_actor_state(proof, block.state_root, address)
+                    .ok_or(Error::<T>::VerificationError)?;
+            let proven = ActorState {
+                code,
+                head,
+                nonce,
+                balance,
+            };
+            ensure!(*state == proven, Error::<T>::VerificationError);
+            Ok(())
+        }
+
+        /// Returns the deal `deal_id` of the storage market actor in the state of `block_cid`
+        pub fn verify_deal_inner(
+            block_cid: BlockCid,
+            deal_id: u64,
+            state_proof: Vec<Vec<u8>>,
+            market_state: Vec<u8>,
+            deal_proof: Vec<Vec<u8>>,
+        ) -> Result<StorageDeal, DispatchError> {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            let (piece_cid, client, provider, start_epoch, end_epoch) =
+                forest_proof_verify::verify_deal(
+                    state_proof,
+                    market_state,
+                    deal_proof,
+                    block.state_root,
+                    deal_id,
+                )
+                .ok_or(Error::<T>::VerificationError)?;
+            Ok(StorageDeal {
+                piece_cid,
+                client,
+                provider,
+                start_epoch,
+                end_epoch,
+            })
+        }
+
+        /// Returns the sector `sector_number` of the miner actor at `miner` in the state
+        /// of `block_cid`
+        pub fn verify_sector_inner(
+            block_cid: BlockCid,
+            miner: Vec<u8>,
+            sector_number: u64,
+            state_proof: Vec<Vec<u8>>,
+            miner_state: Vec<u8>,
+            sector_proof: Vec<Vec<u8>>,
+        ) -> Result<SectorOnChainInfo, DispatchError> {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            let (sector_number, seal_proof, sealed_cid, deal_ids, activation, expiration) =
+                forest_proof_verify::verify_sector(
+                    state_proof,
+                    miner_state,
+                    sector_proof,
+                    block.state_root,
+                    miner,
+                    sector_number,
+                )
+                .ok_or(Error::<T>::VerificationError)?;
+            Ok(SectorOnChainInfo {
+                sector_number,
+                seal_proof,
+                sealed_cid,
+                deal_ids,
+                activation,
+                expiration,
+            })
+        }
+
+        /// Returns the message receipt at `index` of the receipts of `block_cid`
+        pub fn verify_message_receipt_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            index: u64,
+        ) -> Result<MessageReceipt, DispatchError> {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            let (exit_code, return_data, gas_used) =
+                forest_proof_verify::verify_message_receipt(proof, block.receipts_root, index)
+                    .ok_or(Error::<T>::VerificationError)?;
+            Ok(MessageReceipt {
+                exit_code,
+                return_data,
+                gas_used,
+            })
+        }
+
+        /// Returns the CBOR encoded message receipt at `index` of the receipts of
+        /// `block_cid`
+        pub fn verify_receipt_value_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            index: u64,
+        ) -> Result<Vec<u8>, DispatchError> {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            Ok(
+                forest_proof_verify::verify_message_receipt_value(
+                    proof,
+                    block.receipts_root,
+                    index,
+                )
+                .ok_or(Error::<T>::VerificationError)?,
+            )
+        }
+
+        /// Returns the cid of the message at `index` of the secp messages if `secp`, of
+        /// the BLS messages otherwise, of `block_cid`
+        pub fn verify_message_value_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            secp: bool,
+            index: u64,
+        ) -> Result<Vec<u8>, DispatchError> {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            Ok(
+                forest_proof_verify::verify_message(proof, block.message_root, secp, index)
+                    .ok_or(Error::<T>::VerificationError)?,
+            )
+        }
+
+        /// Returns the CBOR encoded state of the actor at `address` in the state of
+        /// `block_cid`
+        pub fn verify_state_value_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            address: Vec<u8>,
+        ) -> Result<Vec<u8>, DispatchError> {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            Ok(
+                forest_proof_verify::verify_actor_state_value(proof, block.state_root, address)
+                    .ok_or(Error::<T>::VerificationError)?,
+            )
+        }
+
+        /// Verify that there is no receipt at `index` of the receipts of `block_cid`
+        pub fn verify_receipt_exclusion_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            index: u64,
         ) -> DispatchResult {
-            forest_proof_verify::verify_state(proof, cid).ok_or(Error::<T>::VerificationError)?;
-            Self::verified_block(&block_cid)
-                .then(|| ())
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            forest_proof_verify::verify_receipt_exclusion(proof, block.receipts_root, index)
                 .ok_or(Error::<T>::VerificationError)?;
             Ok(())
         }
 
-        /// Check if the block cid is already verified
-        fn verified_block(block_cid: &BlockCid) -> bool {
-            VerifiedBlocks::<T>::contains_key(block_cid)
+        /// Verify that there is no message at `index` of the secp messages if `secp`, of
+        /// the BLS messages otherwise, of `block_cid`
+        pub fn verify_message_exclusion_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            secp: bool,
+            index: u64,
+        ) -> DispatchResult {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            forest_proof_verify::verify_message_exclusion(proof, block.message_root, secp, index)
+                .ok_or(Error::<T>::VerificationError)?;
+            Ok(())
+        }
+
+        /// Verify that there is no actor at `address` in the state of `block_cid`
+        pub fn verify_state_exclusion_inner(
+            proof: Vec<Vec<u8>>,
+            block_cid: BlockCid,
+            address: Vec<u8>,
+        ) -> DispatchResult {
+            let block =
+                VerifiedBlocks::<T>::get(&block_cid).ok_or(Error::<T>::VerificationError)?;
+            forest_proof_verify::verify_state_exclusion(proof, block.state_root, address)
+                .ok_or(Error::<T>::VerificationError)?;
+            Ok(())
         }
 
         fn ensure_admin(o: OriginFor<T>) -> DispatchResult {
@@ -443,7 +1296,14 @@ pub mod pallet {
                 !Self::is_relayer(&relayer),
                 Error::<T>::RelayerAlreadyExists
             );
+            ensure!(
+                RelayerCount::<T>::get() < T::MaxRelayers::get(),
+                Error::<T>::TooManyRelayers
+            );
 
+            let bond = T::RelayerBond::get();
+            T::Currency::reserve(&relayer, bond).map_err(|_| Error::<T>::InsufficientBond)?;
+            RelayerBonds::<T>::insert(&relayer, bond);
             Relayers::<T>::insert(&relayer, ());
             RelayerCount::<T>::mutate(|i| {
                 *i = i.saturating_add(1);
@@ -466,6 +1326,7 @@ pub mod pallet {
                 Ok(())
             })?;
             Relayers::<T>::remove(&relayer);
+            T::Currency::unreserve(&relayer, RelayerBonds::<T>::take(&relayer));
 
             Self::deposit_event(Event::RelayerRemoved(relayer));
             Ok(())
@@ -476,12 +1337,223 @@ pub mod pallet {
             Relayers::<T>::contains_key(who)
         }
 
+        /// Replaces a cid in its string representation, as returned by the filecoin
+        /// rpc, with its binary representation, so that the votes for a block are
+        /// counted and the block is verified under a single encoding of its cids
+        fn normalize_cid(cid: &mut Vec<u8>) {
+            if let Some(binary) = frame_support::sp_std::str::from_utf8(cid)
+                .ok()
+                .and_then(crate::ocw::de::decode_cid)
+                // the binary cids of filecoin are CIDv1
+                .filter(|binary| binary.first() == Some(&1))
+            {
+                *cid = binary;
+            }
+        }
+
+        /// Normalizes all the cids of `cids`, see `normalize_cid`
+        fn normalize_cids(cids: &mut [Vec<u8>]) {
+            cids.iter_mut().for_each(Self::normalize_cid);
+        }
+
+        /// Normalizes the roots of a block, see `normalize_cid`
+        fn normalize_roots(
+            message_root: &mut Vec<u8>,
+            state_root: &mut Vec<u8>,
+            receipts_root: &mut Vec<u8>,
+        ) {
+            Self::normalize_cid(message_root);
+            Self::normalize_cid(state_root);
+            Self::normalize_cid(receipts_root);
+        }
+
         // ============== Voting Related =============
+        /// The actual weight of a vote of `weight` which resolved a proposal with the
+        /// votes of `settled` relayers, the votes of `MaxRelayers` relayers are charged
+        /// upfront
+        fn settled_weight(weight: Weight, settled: u32) -> DispatchResultWithPostInfo {
+            Ok(Some(weight.saturating_add(T::WeightInfo::settle_votes(settled))).into())
+        }
+
+        /// Commits the vote of `who` for the tipset `tipset_key`, returns the number of
+        /// votes settled if the vote resolved the proposal
+        fn vote_tipset(
+            who: T::AccountId,
+            mut tipset_key: TipsetKey,
+            height: i64,
+            mut parents: TipsetKey,
+            mut roots: Vec<BlockRoots>,
+            head: i64,
+        ) -> Result<u32, DispatchError> {
+            ensure!(Self::is_relayer(&who), Error::<T>::NotRelayer);
+            Self::normalize_cids(&mut tipset_key);
+            Self::normalize_cids(&mut parents);
+            for r in roots.iter_mut() {
+                Self::normalize_roots(&mut r.message_root, &mut r.state_root, &mut r.receipts_root);
+            }
+            ensure!(
+                !tipset_key.is_empty() && tipset_key.len() == roots.len(),
+                Error::<T>::InvalidTipset
+            );
+            Self::ensure_confirmed(height, head)?;
+            ensure!(
+                !tipset_key
+                    .iter()
+                    .any(|block_cid| VerifiedBlocks::<T>::contains_key(block_cid)),
+                Error::<T>::BlockAlreadyVerified
+            );
+
+            let blocks = roots
+                .into_iter()
+                .map(|roots| VerifiedBlockInfo {
+                    epoch: height,
+                    parents: parents.clone(),
+                    message_root: roots.message_root,
+                    state_root: roots.state_root,
+                    receipts_root: roots.receipts_root,
+                })
+                .collect::<Vec<_>>();
+            Self::ensure_parents_verified(&blocks[0])?;
+
+            TipsetSubmissionProposals::<T>::try_mutate(
+                tipset_key.clone(),
+                |maybe_proposal| -> Result<u32, DispatchError> {
+                    let proposal = maybe_proposal.get_or_insert_with(|| {
+                        let start_block: T::BlockNumber = frame_system::Pallet::<T>::block_number();
+                        let end_block = start_block.saturating_add(VotingPeriod::<T>::get());
+                        let r = BlockSubmissionProposal::new(who.clone(), start_block, end_block);
+                        Self::deposit_event(Event::TipsetProposalCreated(tipset_key.clone()));
+                        r
+                    });
+
+                    match Self::vote_tipset_proposal(
+                        tipset_key.clone(),
+                        blocks,
+                        proposal,
+                        who.clone(),
+                    ) {
+                        Ok(()) => {
+                            Self::deposit_event(Event::TipsetVoteCasted(tipset_key.clone(), who));
+                            let settled = Self::try_resolve_tipset_proposal(tipset_key, proposal);
+                            if settled.is_some() {
+                                *maybe_proposal = None;
+                            }
+                            Ok(settled.unwrap_or(0))
+                        }
+                        Err(e) => match e {
+                            // Resolution is performed lazily, same as the block proposals
+                            Error::<T>::ProposalExpired => {
+                                let settled =
+                                    Self::try_resolve_tipset_proposal(tipset_key, proposal);
+                                if settled.is_some() {
+                                    *maybe_proposal = None;
+                                }
+                                Err(e.into())
+                            }
+                            e => Err(e.into()),
+                        },
+                    }
+                },
+            )
+        }
+
+        /// Commits the vote of `who` for `block_cid` and its info, with `head` the chain
+        /// head seen by `who`. Returns the number of votes settled if the vote resolved
+        /// the proposal
+        fn vote_block(
+            who: T::AccountId,
+            mut block_cid: BlockCid,
+            mut info: VerifiedBlockInfo,
+            head: i64,
+        ) -> Result<u32, DispatchError> {
+            ensure!(Self::is_relayer(&who), Error::<T>::NotRelayer);
+            Self::normalize_cid(&mut block_cid);
+            Self::normalize_cids(&mut info.parents);
+            Self::normalize_roots(
+                &mut info.message_root,
+                &mut info.state_root,
+                &mut info.receipts_root,
+            );
+            Self::ensure_confirmed(info.epoch, head)?;
+            ensure!(
+                !VerifiedBlocks::<T>::contains_key(block_cid.clone()),
+                Error::<T>::BlockAlreadyVerified
+            );
+            Self::ensure_parents_verified(&info)?;
+
+            BlockSubmissionProposals::<T>::try_mutate(
+                block_cid.clone(),
+                |maybe_proposal| -> Result<u32, DispatchError> {
+                    let proposal = maybe_proposal.get_or_insert_with(|| {
+                        let start_block: T::BlockNumber = frame_system::Pallet::<T>::block_number();
+                        let end_block = start_block.saturating_add(VotingPeriod::<T>::get());
+                        let r = BlockSubmissionProposal::new(who.clone(), start_block, end_block);
+                        Self::deposit_event(Event::ProposalCreated(block_cid.clone()));
+                        r
+                    });
+
+                    match Self::vote_block_proposal(block_cid.clone(), info, proposal, who.clone())
+                    {
+                        Ok(()) => {
+                            Self::deposit_event(Event::VoteCasted(block_cid.clone(), who));
+                            let settled = Self::try_resolve_proposal(block_cid, proposal);
+                            if settled.is_some() {
+                                *maybe_proposal = None;
+                            }
+                            Ok(settled.unwrap_or(0))
+                        }
+                        Err(e) => match e {
+                            // Resolution is performed lazily, if it happens to be expired,
+                            // we will issue resolution command.
+                            Error::<T>::ProposalExpired => {
+                                if Self::try_resolve_proposal(block_cid, proposal).is_some() {
+                                    *maybe_proposal = None;
+                                }
+                                Err(e.into())
+                            }
+                            e => Err(e.into()),
+                        },
+                    }
+                },
+            )
+        }
+
+        /// Ensures the epoch `height` is buried under `ConfirmationDepth` epochs in the
+        /// chain of `head`, as reported by the relayer and not checked any further
+        fn ensure_confirmed(height: i64, head: i64) -> DispatchResult {
+            ensure!(
+                height.saturating_add(T::ConfirmationDepth::get()) <= head,
+                Error::<T>::TipsetNotConfirmed
+            );
+            Ok(())
+        }
+
+        /// Ensures the parents of the block are verified and before the block
+        fn ensure_parents_verified(info: &VerifiedBlockInfo) -> DispatchResult {
+            ensure!(!info.parents.is_empty(), Error::<T>::UnknownParent);
+            for parent in info.parents.iter() {
+                let parent = VerifiedBlocks::<T>::get(parent).ok_or(Error::<T>::UnknownParent)?;
+                ensure!(parent.epoch < info.epoch, Error::<T>::InvalidEpoch);
+            }
+            Ok(())
+        }
+
+        /// Returns the verified block at the epoch of `info` with other parents
+        fn conflicting_block(info: &VerifiedBlockInfo) -> Option<BlockCid> {
+            VerifiedBlocksByHeight::<T>::get(info.epoch)
+                .into_iter()
+                .find(|cid| {
+                    VerifiedBlocks::<T>::get(cid)
+                        .map(|block| block.parents != info.parents)
+                        .unwrap_or(false)
+                })
+        }
+
         /// Vote for the proposal. Will reject the operation if its status is invalid
-        /// The content of the vote is actually the message root of the block
+        /// The content of the vote is actually the info of the block
         fn vote_block_proposal(
             block_cid: BlockCid,
-            message_root_cid: Vec<u8>,
+            info: VerifiedBlockInfo,
             proposal: &mut BlockSubmissionProposalOf<T>,
             who: T::AccountId,
         ) -> Result<(), Error<T>> {
@@ -501,23 +1573,30 @@ pub mod pallet {
                 proposal.set_status(ProposalStatus::Rejected);
                 return Err(Error::<T>::ProposalExpired);
             }
+            // the votes of the relayers removed during the vote are kept, so the votes
+            // settled with the proposal are bounded on their own
+            ensure!(
+                proposal.get_votes() < T::MaxRelayers::get(),
+                Error::<T>::TooManyVotes
+            );
+            proposal.add_vote();
             let threshold = VoteThreshold::<T>::get();
 
-            let count =
-                1 + MessageRootCidCounter::<T>::get(&block_cid, &message_root_cid).unwrap_or(0);
+            let count = 1 + MessageRootCidCounter::<T>::get(&block_cid, &info).unwrap_or(0);
             if count >= threshold {
                 proposal.set_status(ProposalStatus::Approved);
             }
 
-            MessageRootCidCounter::<T>::insert(&block_cid, &message_root_cid, count);
-            BlockProposalVotes::<T>::insert(block_cid, who, ());
+            MessageRootCidCounter::<T>::insert(&block_cid, &info, count);
+            BlockProposalVotes::<T>::insert(block_cid, who, info);
 
             Ok(())
         }
 
+        /// Resolve the proposal from the vote `counts` of each content voted on
         pub(crate) fn resolve_proposal(
             proposal: &mut BlockSubmissionProposalOf<T>,
-            block_cid: &[u8],
+            mut counts: impl Iterator<Item = u32>,
             when: &T::BlockNumber,
             threshold: u32,
         ) -> Result<(), Error<T>> {
@@ -529,45 +1608,98 @@ pub mod pallet {
             // when expired, we set the status to be rejected
             if proposal.is_expired(when) {
                 proposal.set_status(ProposalStatus::Rejected);
-            } else {
-                // MessageRootCidCounter leaked into the struct, well not the best way for encapsulation
-                // but works for now, come back later to fix this.
-                for (_, count) in MessageRootCidCounter::<T>::iter_prefix(block_cid) {
-                    if count >= threshold {
-                        proposal.set_status(ProposalStatus::Approved);
-                        break;
-                    }
-                }
+            } else if counts.any(|count| count >= threshold) {
+                proposal.set_status(ProposalStatus::Approved);
             }
 
             Ok(())
         }
 
-        /// Try to resolve the proposal. If the proposal is resolved, return true, else false
-        fn try_resolve_proposal(block_cid: BlockCid, prop: &BlockSubmissionProposalOf<T>) -> bool {
+        /// Try to resolve the proposal. If the proposal is resolved, return the number of
+        /// its votes settled, else None
+        fn try_resolve_proposal(
+            block_cid: BlockCid,
+            prop: &BlockSubmissionProposalOf<T>,
+        ) -> Option<u32> {
             match prop.get_status() {
                 ProposalStatus::Approved => {
                     Self::finalize_block(block_cid);
-                    true
+                    Some(prop.get_votes())
                 }
                 ProposalStatus::Rejected => {
                     Self::reject_block(block_cid);
-                    true
+                    Some(prop.get_votes())
                 }
-                _ => false,
+                _ => None,
             }
         }
 
-        fn finalize_block(block_cid: BlockCid) {
+        pub(crate) fn finalize_block(block_cid: BlockCid) {
+            // the info with the most votes is the one that passed the threshold
+            let info = MessageRootCidCounter::<T>::iter_prefix(&block_cid)
+                .max_by_key(|(_, count)| *count)
+                .map(|(info, _)| info);
+
             BlockSubmissionProposals::<T>::remove(&block_cid);
-            BlockProposalVotes::<T>::remove_prefix(&block_cid, None);
+            let votes = BlockProposalVotes::<T>::drain_prefix(&block_cid).collect::<Vec<_>>();
             MessageRootCidCounter::<T>::remove_prefix(&block_cid, None);
 
-            VerifiedBlocks::<T>::insert(block_cid.clone(), ());
+            if let Some(info) = info {
+                // the block verified first is kept, so that the verified blocks
+                // stay a chain whatever the order the forks are approved in
+                if let Some(verified) = Self::conflicting_block(&info) {
+                    // the relayers may have followed the other fork, the votes are not settled
+                    Self::deposit_event(Event::ForkDetected(info.epoch, verified, block_cid));
+                    return;
+                }
+
+                Self::settle_votes(votes, &info);
+
+                VerifiedBlocksByHeight::<T>::append(info.epoch, block_cid.clone());
+                VerifiedBlocks::<T>::insert(block_cid.clone(), info);
+            }
 
             Self::deposit_event(Event::ProposalApproved(block_cid));
         }
 
+        /// Rewards the relayers who voted for the `approved` info of a proposal, the
+        /// others are slashed
+        fn settle_votes<I: PartialEq>(votes: Vec<(T::AccountId, I)>, approved: &I) {
+            for (who, voted) in votes {
+                if &voted == approved {
+                    PendingRewards::<T>::mutate(&who, |rewards| {
+                        *rewards = rewards.saturating_add(T::RelayerReward::get())
+                    });
+                } else {
+                    Self::slash_relayer(who);
+                }
+            }
+        }
+
+        /// Slashes the bond of the relayer `who` to `T::Slash` and removes it from the
+        /// set, the admin adds it back once it can reserve a new bond. Same as
+        /// `unregister_relayer`, the relayer is kept without bond when there would be
+        /// less relayers than `VoteThreshold` left, until the admin replaces it.
+        fn slash_relayer(who: T::AccountId) {
+            // the relayer may have been slashed already for another vote
+            if !Self::is_relayer(&who) {
+                return;
+            }
+            let bond = RelayerBonds::<T>::take(&who);
+            let (imbalance, remaining) = T::Currency::slash_reserved(&who, bond);
+            T::Slash::on_unbalanced(imbalance);
+            Self::deposit_event(Event::RelayerSlashed(
+                who.clone(),
+                bond.saturating_sub(remaining),
+            ));
+
+            if RelayerCount::<T>::get() > VoteThreshold::<T>::get() {
+                Relayers::<T>::remove(&who);
+                RelayerCount::<T>::mutate(|i| *i = i.saturating_sub(1));
+                Self::deposit_event(Event::RelayerRemoved(who));
+            }
+        }
+
         fn reject_block(block_cid: BlockCid) {
             BlockSubmissionProposals::<T>::remove(&block_cid);
             MessageRootCidCounter::<T>::remove_prefix(&block_cid, None);
@@ -576,17 +1708,142 @@ pub mod pallet {
 
             Self::deposit_event(Event::ProposalRejected(block_cid));
         }
+
+        /// Vote for the tipset proposal, same as `vote_block_proposal` with the info of
+        /// all the blocks of the tipset as the content of the vote
+        fn vote_tipset_proposal(
+            tipset_key: TipsetKey,
+            blocks: Vec<VerifiedBlockInfo>,
+            proposal: &mut BlockSubmissionProposalOf<T>,
+            who: T::AccountId,
+        ) -> Result<(), Error<T>> {
+            ensure!(
+                !TipsetProposalVotes::<T>::contains_key(tipset_key.clone(), who.clone()),
+                Error::<T>::AlreadyVoted
+            );
+            ensure!(
+                *proposal.get_status() == ProposalStatus::Active,
+                Error::<T>::ProposalCompleted
+            );
+
+            let now = frame_system::Pallet::<T>::block_number();
+
+            // when expired, we set the status to be rejected
+            if proposal.is_expired(&now) {
+                proposal.set_status(ProposalStatus::Rejected);
+                return Err(Error::<T>::ProposalExpired);
+            }
+            // the votes of the relayers removed during the vote are kept, so the votes
+            // settled with the proposal are bounded on their own
+            ensure!(
+                proposal.get_votes() < T::MaxRelayers::get(),
+                Error::<T>::TooManyVotes
+            );
+            proposal.add_vote();
+            let threshold = VoteThreshold::<T>::get();
+
+            let count = 1 + TipsetInfoCounter::<T>::get(&tipset_key, &blocks).unwrap_or(0);
+            if count >= threshold {
+                proposal.set_status(ProposalStatus::Approved);
+            }
+
+            TipsetInfoCounter::<T>::insert(&tipset_key, &blocks, count);
+            TipsetProposalVotes::<T>::insert(tipset_key, who, blocks);
+
+            Ok(())
+        }
+
+        /// Try to resolve the tipset proposal, same as `try_resolve_proposal`
+        fn try_resolve_tipset_proposal(
+            tipset_key: TipsetKey,
+            prop: &BlockSubmissionProposalOf<T>,
+        ) -> Option<u32> {
+            match prop.get_status() {
+                ProposalStatus::Approved => {
+                    Self::finalize_tipset(tipset_key);
+                    Some(prop.get_votes())
+                }
+                ProposalStatus::Rejected => {
+                    Self::reject_tipset(tipset_key);
+                    Some(prop.get_votes())
+                }
+                _ => None,
+            }
+        }
+
+        fn finalize_tipset(tipset_key: TipsetKey) {
+            // the info with the most votes is the one that passed the threshold
+            let blocks = TipsetInfoCounter::<T>::iter_prefix(&tipset_key)
+                .max_by_key(|(_, count)| *count)
+                .map(|(blocks, _)| blocks);
+
+            TipsetSubmissionProposals::<T>::remove(&tipset_key);
+            let votes = TipsetProposalVotes::<T>::drain_prefix(&tipset_key).collect::<Vec<_>>();
+            TipsetInfoCounter::<T>::remove_prefix(&tipset_key, None);
+
+            if let Some(blocks) = blocks {
+                // the blocks of a tipset share their parents, they all conflict with
+                // a verified block if any of them does
+                if let Some(verified) = Self::conflicting_block(&blocks[0]) {
+                    // the relayers may have followed the other fork, the votes are not settled
+                    Self::deposit_event(Event::ForkDetected(
+                        blocks[0].epoch,
+                        verified,
+                        tipset_key[0].clone(),
+                    ));
+                    return;
+                }
+
+                Self::settle_votes(votes, &blocks);
+                for (block_cid, info) in tipset_key.iter().zip(blocks) {
+                    // the block may have been verified on its own during the vote
+                    if VerifiedBlocks::<T>::contains_key(block_cid) {
+                        continue;
+                    }
+                    VerifiedBlocksByHeight::<T>::append(info.epoch, block_cid.clone());
+                    VerifiedBlocks::<T>::insert(block_cid, info);
+                }
+            }
+
+            Self::deposit_event(Event::TipsetApproved(tipset_key));
+        }
+
+        fn reject_tipset(tipset_key: TipsetKey) {
+            TipsetSubmissionProposals::<T>::remove(&tipset_key);
+            TipsetProposalVotes::<T>::remove_prefix(&tipset_key, None);
+            TipsetInfoCounter::<T>::remove_prefix(&tipset_key, None);
+
+            Self::deposit_event(Event::TipsetRejected(tipset_key));
+        }
     }
 
     pub trait WeightInfo {
         fn add_relayer() -> Weight;
         fn remove_relayer() -> Weight;
         fn submit_block_vote() -> Weight;
+        fn submit_block_header() -> Weight;
         fn set_vote_threshold() -> Weight;
+        fn claim_rewards() -> Weight;
         fn close_block_proposal() -> Weight;
+        fn submit_tipset_vote(n: u32) -> Weight;
+        fn submit_aggregated_votes(n: u32, r: u32) -> Weight;
+        fn close_tipset_proposal() -> Weight;
+        fn settle_votes(v: u32) -> Weight;
+        fn set_checkpoint() -> Weight;
+        fn verify_ancestry(n: u32) -> Weight;
         fn verify_receipt() -> Weight;
         fn verify_state() -> Weight;
         fn verify_message() -> Weight;
+        fn verify_actor_state() -> Weight;
+        fn verify_deal() -> Weight;
+        fn verify_sector() -> Weight;
+        fn verify_message_receipt() -> Weight;
+        fn verify_receipt_value() -> Weight;
+        fn verify_state_value() -> Weight;
+        fn verify_message_value() -> Weight;
+        fn verify_receipt_exclusion() -> Weight;
+        fn verify_state_exclusion() -> Weight;
+        fn verify_message_exclusion() -> Weight;
     }
 
     /// For backwards compatibility and tests
@@ -603,14 +1860,46 @@ pub mod pallet {
             Default::default()
         }
 
+        fn submit_block_header() -> Weight {
+            Default::default()
+        }
+
         fn set_vote_threshold() -> Weight {
             Default::default()
         }
 
+        fn claim_rewards() -> Weight {
+            Default::default()
+        }
+
         fn close_block_proposal() -> Weight {
             Default::default()
         }
 
+        fn submit_tipset_vote(_n: u32) -> Weight {
+            Default::default()
+        }
+
+        fn submit_aggregated_votes(_n: u32, _r: u32) -> Weight {
+            Default::default()
+        }
+
+        fn close_tipset_proposal() -> Weight {
+            Default::default()
+        }
+
+        fn settle_votes(_v: u32) -> Weight {
+            Default::default()
+        }
+
+        fn set_checkpoint() -> Weight {
+            Default::default()
+        }
+
+        fn verify_ancestry(_n: u32) -> Weight {
+            Default::default()
+        }
+
         fn verify_receipt() -> Weight {
             Default::default()
         }
@@ -622,5 +1911,45 @@ pub mod pallet {
         fn verify_message() -> Weight {
             Default::default()
         }
+
+        fn verify_actor_state() -> Weight {
+            Default::default()
+        }
+
+        fn verify_deal() -> Weight {
+            Default::default()
+        }
+
+        fn verify_sector() -> Weight {
+            Default::default()
+        }
+
+        fn verify_message_receipt() -> Weight {
+            Default::default()
+        }
+
+        fn verify_receipt_value() -> Weight {
+            Default::default()
+        }
+
+        fn verify_state_value() -> Weight {
+            Default::default()
+        }
+
+        fn verify_message_value() -> Weight {
+            Default::default()
+        }
+
+        fn verify_receipt_exclusion() -> Weight {
+            Default::default()
+        }
+
+        fn verify_state_exclusion() -> Weight {
+            Default::default()
+        }
+
+        fn verify_message_exclusion() -> Weight {
+            Default::default()
+        }
     }
 }
@@ -3,19 +3,66 @@
 
 //! Filecoin APIs
 
-pub use self::chain_head::ChainHead;
+pub use self::{chain_get_tip_set_by_height::ChainGetTipSetByHeight, chain_head::ChainHead};
+use crate::ocw::result::Error;
 use frame_support::{
+    codec::{Decode, Encode},
     log,
     sp_runtime::offchain::{
-        http::{Error, Request},
+        http::{self, Request},
         Timestamp,
     },
     sp_std::{vec, vec::Vec},
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+mod chain_get_tip_set_by_height;
 mod chain_head;
 
+/// Filecoin rpc endpoint as stored in the offchain storage
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct EndpointConfig {
+    /// The http url of the endpoint
+    pub url: Vec<u8>,
+    /// The headers added to each request, e.g. the authorization token of the node
+    pub headers: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Filecoin rpc endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Endpoint<'e> {
+    /// The http url of the endpoint
+    pub url: &'e str,
+    /// The headers added to each request
+    pub headers: Vec<(&'e str, &'e str)>,
+}
+
+impl<'e> From<&'e str> for Endpoint<'e> {
+    fn from(url: &'e str) -> Self {
+        Self {
+            url,
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl<'e> TryFrom<&'e EndpointConfig> for Endpoint<'e> {
+    type Error = core::str::Utf8Error;
+
+    fn try_from(config: &'e EndpointConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: core::str::from_utf8(&config.url)?,
+            headers: config
+                .headers
+                .iter()
+                .map(|(name, value)| {
+                    Ok((core::str::from_utf8(name)?, core::str::from_utf8(value)?))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
 /// Wrapper for jsonrpc result
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Resp<T> {
@@ -23,6 +70,23 @@ pub struct Resp<T> {
     pub result: T,
 }
 
+/// Wrapper for jsonrpc error
+#[derive(Clone, Debug, Deserialize)]
+pub struct ErrorResp {
+    /// JsonRPC error
+    pub error: RpcError,
+}
+
+/// JsonRPC error object
+#[derive(Clone, Debug, Deserialize)]
+pub struct RpcError {
+    /// error code
+    pub code: i64,
+    /// error message
+    #[serde(deserialize_with = "crate::ocw::de::bytes")]
+    pub message: Vec<u8>,
+}
+
 /// Request JSON body
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Req<T> {
@@ -44,11 +108,11 @@ pub trait Api: Sized {
 
     fn iter_req(
         &self,
-        endpoints: &[&str],
+        endpoints: &[Endpoint],
         params: Self::Params,
         deadline: Timestamp,
     ) -> Result<Self::Result, Error> {
-        let mut result = Err(Error::IoError);
+        let mut result = Err(Error::FilecoinRpcNotSet);
         for endpoint in endpoints {
             result = self.req(endpoint, params.clone(), deadline);
             if result.is_ok() {
@@ -62,7 +126,7 @@ pub trait Api: Sized {
     /// Request method with params
     fn req(
         &self,
-        endpoint: &str,
+        endpoint: &Endpoint,
         params: Self::Params,
         deadline: Timestamp,
     ) -> Result<Self::Result, Error> {
@@ -72,34 +136,53 @@ pub trait Api: Sized {
             jsonrpc: "2.0",
             params,
         })
-        .map_err(|_| Error::IoError)?;
+        .map_err(|_| Error::HttpError)?;
 
         // build request
-        let req = Request::post(endpoint, vec![body])
+        let mut req = Request::post(endpoint.url, vec![body])
             .add_header("Content-Type", "application/json")
             .deadline(deadline);
+        for (name, value) in &endpoint.headers {
+            req = req.add_header(name, value);
+        }
 
         // get response
         let resp = req
             .send()
             .map_err(|e| {
                 log::error!("send request failed {:?}", e);
-                Error::IoError
+                Error::HttpError
             })?
             .wait()
             .map_err(|e| {
                 log::error!("wait request faild {:?}", e);
-                Error::IoError
-            })?
-            .body()
-            .collect::<Vec<_>>();
+                match e {
+                    http::Error::DeadlineReached => Error::Timeout,
+                    _ => Error::HttpError,
+                }
+            })?;
+        let code = resp.code;
+        let body = resp.body().collect::<Vec<_>>();
+
+        if code == 401 || code == 403 {
+            return Err(Error::Unauthorized);
+        }
+
+        // lotus answers the failed calls with an error object
+        if let Ok(ErrorResp { error }) = serde_json::from_slice::<ErrorResp>(&body) {
+            return Err(Error::RpcError(error.code, error.message));
+        }
+
+        if code != 200 {
+            return Err(Error::HttpStatus(code));
+        }
 
         // deserialize response
-        Ok(serde_json::from_slice::<Resp<Self::Result>>(&resp)
+        Ok(serde_json::from_slice::<Resp<Self::Result>>(&body)
             .map_err(|e| {
-                log::error!("result {:?}", resp);
+                log::error!("result {:?}", body);
                 log::error!("parse result failed {:?}", e);
-                Error::IoError
+                Error::InvalidResponse
             })?
             .result)
     }
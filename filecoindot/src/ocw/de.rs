@@ -17,3 +17,40 @@ where
         Err(D::Error::custom("field not string"))
     }
 }
+
+/// deserialize the json string of a cid to the binary cid, as the cids are read from
+/// the block headers by the pallet
+pub fn cid<'de, D>(data: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(data)?;
+    if let Value::String(s) = value {
+        decode_cid(&s).ok_or_else(|| D::Error::custom("invalid cid"))
+    } else {
+        Err(D::Error::custom("field not string"))
+    }
+}
+
+/// decode the string of a cid to the binary cid, the cids of filecoin are CIDv1 encoded
+/// in multibase base32, i.e. lowercase RFC 4648 base32 without padding prefixed with `b`
+pub fn decode_cid(s: &str) -> Option<Vec<u8>> {
+    let encoded = s.strip_prefix('b')?;
+    let mut cid = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u16, 0u16);
+    for c in encoded.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            cid.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(cid)
+}
@@ -6,47 +6,71 @@
 use crate::{
     crypto::FilecoindotId,
     ocw::{
-        api::{Api, ChainHead},
+        api::{Api, ChainGetTipSetByHeight, ChainHead, Endpoint, EndpointConfig},
         result::{Error, Result},
+        types::TipSetVote,
     },
-    Call, Config, Relayers,
+    Call, Config, Relayers, TipsetProposalVotes, TipsetVotePayload, VerifiedBlocks,
 };
 use frame_support::{
     codec::Encode,
+    log, sp_io,
     sp_runtime::{
-        offchain::{storage::StorageValueRef, Timestamp},
+        offchain::{
+            storage::StorageValueRef,
+            storage_lock::{StorageLock, Time},
+            Duration, Timestamp,
+        },
         traits::Verify,
         RuntimeAppPublic,
     },
-    sp_std::vec::Vec,
+    sp_std::{vec, vec::Vec},
     traits::Get,
 };
-use frame_system::offchain::{SendSignedTransaction, Signer};
+use frame_system::offchain::{SendUnsignedTransaction, Signer};
 use sp_core::sr25519::Signature as Sr25519Signature;
 
 pub mod api;
-mod de;
-mod result;
+pub(crate) mod de;
+pub(crate) mod result;
 pub mod types;
 
 #[cfg(test)]
 mod tests;
 
-/// the storage key of filecoin rpc endpoint
+/// the storage key of filecoin rpc endpoints, see [`EndpointConfig`]
 pub const FILECOIN_RPC: &[u8] = b"FILECOIN_RPC";
 
+/// the storage key of the number of filecoin rpc endpoints required to agree
+pub const FILECOIN_RPC_QUORUM: &[u8] = b"FILECOIN_RPC_QUORUM";
+
+/// the storage key of the last epoch voted by the relayer
+pub const LAST_VOTED_EPOCH: &[u8] = b"LAST_VOTED_EPOCH";
+
+/// the maximum number of epochs walked through in one run of the ocw
+pub const MAX_BACKFILL_EPOCHS: i64 = 20;
+
+/// the storage key of the lock held by the ocw while voting
+pub const VOTE_LOCK: &[u8] = b"VOTE_LOCK";
+
+/// the storage key of the tipsets the relayer submitted votes for
+pub const SUBMITTED_VOTES: &[u8] = b"SUBMITTED_VOTES";
+
+/// the maximum number of tipsets remembered in `SUBMITTED_VOTES`
+pub const MAX_SUBMITTED_VOTES: usize = 100;
+
 /// offchain worker entry
 pub fn offchain_worker<T: Config>(block_number: T::BlockNumber) -> Result<()> {
-    // get encoded urls from storage
-    let urls = StorageValueRef::persistent(FILECOIN_RPC)
-        .get::<Vec<Vec<u8>>>()
+    // get encoded endpoints from storage
+    let configs = StorageValueRef::persistent(FILECOIN_RPC)
+        .get::<Vec<EndpointConfig>>()
         .map_err(|_| Error::GetStorageFailed)?
         .ok_or(Error::FilecoinRpcNotSet)?;
 
     // decode endpoints
-    let endpoints: Vec<&str> = urls
+    let endpoints: Vec<Endpoint> = configs
         .iter()
-        .map(|url_bytes| core::str::from_utf8(url_bytes))
+        .map(Endpoint::try_from)
         .collect::<core::result::Result<Vec<_>, _>>()
         .map_err(|_| Error::FormatBytesFailed)?;
 
@@ -55,14 +79,23 @@ pub fn offchain_worker<T: Config>(block_number: T::BlockNumber) -> Result<()> {
         return Err(Error::FilecoinRpcNotSet);
     }
 
+    // the first endpoint answering is trusted by default
+    let quorum = StorageValueRef::persistent(FILECOIN_RPC_QUORUM)
+        .get::<u32>()
+        .map_err(|_| Error::GetStorageFailed)?
+        .unwrap_or(1) as usize;
+    if quorum > endpoints.len() {
+        return Err(Error::NoQuorum);
+    }
+
     // bootstrap ocw
-    bootstrap::<T>(block_number, &endpoints)?;
+    bootstrap::<T>(block_number, &endpoints, quorum)?;
 
     Ok(())
 }
 
 /// bootstrap filcoindot ocw
-fn bootstrap<T: Config>(_: T::BlockNumber, urls: &[&str]) -> Result<()> {
+fn bootstrap<T: Config>(_: T::BlockNumber, endpoints: &[Endpoint], quorum: usize) -> Result<()> {
     let all_public: Vec<Vec<u8>> = <FilecoindotId as frame_system::offchain::AppCrypto<
         <Sr25519Signature as Verify>::Signer,
         Sr25519Signature,
@@ -72,49 +105,204 @@ fn bootstrap<T: Config>(_: T::BlockNumber, urls: &[&str]) -> Result<()> {
     .collect();
 
     // check if keystore has key listed in Relayers
-    let mut no_relayer = true;
-    if Relayers::<T>::iter().any(|relayer| all_public.contains(&relayer.encode())) {
-        no_relayer = false;
-    }
+    let relayers: Vec<T::AccountId> = Relayers::<T>::iter_keys()
+        .filter(|relayer| all_public.contains(&relayer.encode()))
+        .collect();
 
     let signer = Signer::<T, T::AuthorityId>::any_account();
-    if !signer.can_sign() || no_relayer {
+    if !signer.can_sign() || relayers.is_empty() {
         return Err(Error::NoRelayerFound);
     }
 
-    vote_on_chain_head(signer, urls)
+    vote_on_confirmed_tipsets::<T, _>(&relayers, endpoints, quorum, |vote, head| {
+        vote_on_tipset(&signer, vote, head)
+    })
 }
 
-fn vote_on_chain_head<T: Config>(signer: Signer<T, T::AuthorityId>, urls: &[&str]) -> Result<()> {
-    let pairs = ChainHead
-        .iter_req(
-            urls,
-            Vec::new(),
-            Timestamp::from_unix_millis(T::OffchainWorkerTimeout::get()),
-        )
-        .map_err(|_| Error::HttpError)?
-        .pairs()?;
+/// vote on the tipsets missed since the last voted epoch, up to the tipset
+/// `ConfirmationDepth` epochs below the chain head, the votes are sent with `submit`
+pub(crate) fn vote_on_confirmed_tipsets<T, F>(
+    relayers: &[T::AccountId],
+    endpoints: &[Endpoint],
+    quorum: usize,
+    submit: F,
+) -> Result<()>
+where
+    T: Config,
+    F: Fn(TipSetVote, i64) -> Result<()>,
+{
+    // overlapping workers would submit the same votes
+    let mut lock = StorageLock::<Time>::with_deadline(
+        VOTE_LOCK,
+        Duration::from_millis(T::OffchainWorkerTimeout::get()),
+    );
+    let _guard = lock.try_lock().map_err(|_| Error::VoteLocked)?;
 
-    if pairs
-        .into_iter()
-        .map(|(cid, msg_root)| {
-            // FIXME:
-            //
-            // still requires taking the ownership even under `into_iter()`
-            let (_, res) = signer
-                .send_signed_transaction(|_| Call::submit_block_vote {
-                    block_cid: cid.to_vec(),
-                    message_root_cid: msg_root.to_vec(),
-                })
-                .ok_or(Error::NoTxResult)?;
-
-            let _ = res.map_err(|_| Error::OffchainSignedTxError)?;
-            Ok(())
-        })
-        .any(|x| x == Err(Error::OffchainSignedTxError))
+    // the requests of this run share the timeout, from now on
+    let deadline =
+        sp_io::offchain::timestamp().add(Duration::from_millis(T::OffchainWorkerTimeout::get()));
+    let head = chain_head(endpoints, deadline, quorum)?;
+    let confirmed = head.saturating_sub(T::ConfirmationDepth::get());
+    if confirmed < 0 {
+        return Err(Error::ChainTooShort);
+    }
+
+    // start from the confirmed tipset if this relayer has never voted
+    let last_voted = StorageValueRef::persistent(LAST_VOTED_EPOCH);
+    let start = match last_voted
+        .get::<i64>()
+        .map_err(|_| Error::GetStorageFailed)?
     {
-        return Err(Error::OffchainSignedTxError);
+        Some(epoch) => epoch.saturating_add(1),
+        None => confirmed,
+    };
+    let end = confirmed.min(start.saturating_add(MAX_BACKFILL_EPOCHS - 1));
+
+    let submitted_votes = StorageValueRef::persistent(SUBMITTED_VOTES);
+    let mut submitted = submitted_votes
+        .get::<Vec<Vec<Vec<u8>>>>()
+        .map_err(|_| Error::GetStorageFailed)?
+        .unwrap_or_default();
+
+    for epoch in start..=end {
+        let tipset = quorum_req(
+            &ChainGetTipSetByHeight,
+            endpoints,
+            vec![Some(epoch as u64), None],
+            deadline,
+            quorum,
+        )?;
+
+        // the previous tipset is returned for a null round, it has been voted already
+        if tipset.height == epoch {
+            let vote = tipset.vote()?;
+            // the votes are rejected until the parents are verified, the
+            // next runs resume from this epoch
+            if !vote
+                .parents
+                .iter()
+                .all(|parent| VerifiedBlocks::<T>::contains_key(parent))
+            {
+                break;
+            }
+            if !has_voted::<T>(relayers, &submitted, &vote) {
+                let tipset_key = vote.tipset_key.clone();
+                // the epoch is voted on again by the next runs
+                if let Err(e) = submit(vote, head) {
+                    log::error!("failed to submit the vote at epoch {}: {}", epoch, e);
+                    break;
+                }
+
+                submitted.push(tipset_key);
+                if submitted.len() > MAX_SUBMITTED_VOTES {
+                    submitted.remove(0);
+                }
+                submitted_votes.set(&submitted);
+            }
+        }
+
+        // only reached once the tipset at `epoch` is voted on or skipped
+        last_voted.set(&epoch);
     }
 
     Ok(())
 }
+
+/// get the height of the chain head, the endpoints may not be in sync so the
+/// highest height reported by at least `quorum` of them is used
+fn chain_head(endpoints: &[Endpoint], deadline: Timestamp, quorum: usize) -> Result<i64> {
+    if quorum <= 1 {
+        return ChainHead
+            .iter_req(endpoints, Vec::new(), deadline)
+            .map(|tipset| tipset.height);
+    }
+
+    let mut heights = endpoints
+        .iter()
+        .filter_map(|endpoint| ChainHead.req(endpoint, Vec::new(), deadline).ok())
+        .map(|tipset| tipset.height)
+        .collect::<Vec<_>>();
+    heights.sort_unstable_by(|a, b| b.cmp(a));
+    heights.get(quorum - 1).copied().ok_or(Error::NoQuorum)
+}
+
+/// request all the endpoints, the result returned identically by at least
+/// `quorum` of them is used
+fn quorum_req<A: Api>(
+    api: &A,
+    endpoints: &[Endpoint],
+    params: A::Params,
+    deadline: Timestamp,
+    quorum: usize,
+) -> Result<A::Result>
+where
+    A::Result: PartialEq,
+{
+    if quorum <= 1 {
+        return api.iter_req(endpoints, params, deadline);
+    }
+
+    let mut results: Vec<(A::Result, Vec<&str>)> = Vec::new();
+    for endpoint in endpoints {
+        match api.req(endpoint, params.clone(), deadline) {
+            Ok(result) => match results.iter_mut().find(|(r, _)| *r == result) {
+                Some((_, agreed)) => agreed.push(endpoint.url),
+                None => results.push((result, vec![endpoint.url])),
+            },
+            Err(e) => log::warn!("request {} to {} failed {:?}", A::METHOD, endpoint.url, e),
+        }
+    }
+
+    if results.len() > 1 {
+        log::warn!("filecoin rpc endpoints disagree on {}", A::METHOD);
+        for (result, agreed) in &results {
+            log::warn!("endpoints {:?} returned {:?}", agreed, result);
+        }
+    }
+
+    results
+        .into_iter()
+        .find(|(_, agreed)| agreed.len() >= quorum)
+        .map(|(result, _)| result)
+        .ok_or(Error::NoQuorum)
+}
+
+/// check if the relayer has submitted the vote or if the pallet would reject it
+fn has_voted<T: Config>(
+    relayers: &[T::AccountId],
+    submitted: &[Vec<Vec<u8>>],
+    vote: &TipSetVote,
+) -> bool {
+    submitted.contains(&vote.tipset_key)
+        || vote
+            .tipset_key
+            .iter()
+            .any(|block_cid| VerifiedBlocks::<T>::contains_key(block_cid))
+        || relayers
+            .iter()
+            .any(|relayer| TipsetProposalVotes::<T>::contains_key(&vote.tipset_key, relayer))
+}
+
+/// the blocks of the tipset are voted in one transaction, the vote is signed by
+/// the relayer and sent unsigned so the relayer does not pay fees
+fn vote_on_tipset<T: Config>(
+    signer: &Signer<T, T::AuthorityId>,
+    vote: TipSetVote,
+    head: i64,
+) -> Result<()> {
+    let (_, res) = signer
+        .send_unsigned_transaction(
+            |account| TipsetVotePayload {
+                tipset_key: vote.tipset_key.clone(),
+                height: vote.height,
+                parents: vote.parents.clone(),
+                roots: vote.roots.clone(),
+                head,
+                public: account.public.clone(),
+            },
+            |payload, signature| Call::submit_tipset_vote_unsigned { payload, signature },
+        )
+        .ok_or(Error::NoTxResult)?;
+
+    res.map_err(|_| Error::OffchainUnsignedTxError)
+}
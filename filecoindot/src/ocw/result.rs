@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: LGPL-3.0-only
 #![allow(clippy::enum_variant_names)]
 use derive_more::Display;
+use frame_support::sp_std::vec::Vec;
 
 #[derive(Debug, Display, PartialEq, Eq)]
 pub enum Error {
@@ -11,12 +12,32 @@ pub enum Error {
     GetStorageFailed,
     #[display(fmt = "haven't set filecoin rpc yet")]
     FilecoinRpcNotSet,
-    #[display(fmt = "blocks and cids not matched in tipset")]
+    #[display(fmt = "blocks and cids not matched or parents differ in tipset")]
     InvalidTipSet,
+    #[display(fmt = "chain head is below the confirmation depth")]
+    ChainTooShort,
+    #[display(fmt = "another worker is voting")]
+    VoteLocked,
+    #[display(fmt = "not enough filecoin rpc endpoints agree")]
+    NoQuorum,
     #[display(fmt = "http request failed")]
     HttpError,
-    #[display(fmt = "signed tx error")]
-    OffchainSignedTxError,
+    #[display(fmt = "http request timed out")]
+    Timeout,
+    #[display(fmt = "filecoin rpc rejected the credentials")]
+    Unauthorized,
+    #[display(fmt = "filecoin rpc answered with http status {}", _0)]
+    HttpStatus(u16),
+    #[display(
+        fmt = "filecoin rpc error {}: {}",
+        _0,
+        "core::str::from_utf8(_1).unwrap_or_default()"
+    )]
+    RpcError(i64, Vec<u8>),
+    #[display(fmt = "failed to parse filecoin rpc response")]
+    InvalidResponse,
+    #[display(fmt = "unsigned tx error")]
+    OffchainUnsignedTxError,
     #[display(fmt = "no tx result yet")]
     NoTxResult,
     #[display(fmt = "no relayer found")]
@@ -3,12 +3,10 @@
 #![allow(unused_imports)]
 mod data;
 mod ext;
-mod get_tip_set_by_height;
 
-use crate::ocw::api::{Api, ChainHead};
+use crate::ocw::api::{Api, ChainGetTipSetByHeight, ChainHead};
 use ext::OffchainExt;
 use frame_support::sp_runtime::offchain::Timestamp;
-use get_tip_set_by_height::ChainGetTipSetByHeight;
 use sp_core::offchain::{OffchainDbExt, OffchainWorkerExt};
 
 // infura rpc for testing
@@ -25,7 +23,7 @@ const FILECOIN_RPC: &str = "https://api.node.glif.io";
 //         assert_eq!(
 //             ChainGetTipSetByHeight
 //                 .req(
-//                     FILECOIN_RPC,
+//                     &FILECOIN_RPC.into(),
 //                     vec![Some(1199840), None],
 //                     Timestamp::from_unix_millis(1_000_000)
 //                 )
@@ -44,7 +42,27 @@ fn test_can_parse_chain_head() {
 
     t.execute_with(|| {
         assert!(ChainHead
-            .req(FILECOIN_RPC, vec![], Timestamp::from_unix_millis(1_000_000))
+            .req(
+                &FILECOIN_RPC.into(),
+                vec![],
+                Timestamp::from_unix_millis(1_000_000)
+            )
             .is_ok());
     })
 }
+
+#[test]
+fn test_decode_cid() {
+    let cid = crate::ocw::de::decode_cid(
+        "bafy2bzacedbaliyx3k64d4rxy5q2og3wf5r5e2ra6bvf52ogldc6oad3jukbe",
+    )
+    .unwrap();
+    // CIDv1, dag-cbor, blake2b-256 of 32 bytes
+    assert_eq!(cid[..6], [0x01, 0x71, 0xa0, 0xe4, 0x02, 0x20]);
+    assert_eq!(cid.len(), 38);
+    assert_eq!(cid[6..8], [0xc2, 0x05]);
+
+    // not multibase base32
+    assert!(crate::ocw::de::decode_cid("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG").is_none());
+    assert!(crate::ocw::de::decode_cid("bafy0").is_none());
+}
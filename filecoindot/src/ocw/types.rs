@@ -3,7 +3,10 @@
 
 //! Filecoin api types
 #![allow(missing_docs)]
-use crate::ocw::{Error, Result};
+use crate::{
+    ocw::{Error, Result},
+    types::BlockRoots,
+};
 use frame_support::sp_std::vec::Vec;
 use serde::{Deserialize, Serialize};
 
@@ -23,31 +26,61 @@ pub struct TipSet {
     pub height: i64,
 }
 
+/// The content of the vote of a relayer for a tipset
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TipSetVote {
+    pub tipset_key: Vec<Vec<u8>>,
+    pub height: i64,
+    pub parents: Vec<Vec<u8>>,
+    pub roots: Vec<BlockRoots>,
+}
+
 impl TipSet {
-    /// get (cid, message_root) pairs
-    pub fn pairs(self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
-        if self.cids.len() != self.blocks.len() {
+    /// get the vote of the tipset, the roots are in the order of the cids
+    pub fn vote(self) -> Result<TipSetVote> {
+        if self.cids.is_empty() || self.cids.len() != self.blocks.len() {
+            return Err(Error::InvalidTipSet);
+        }
+
+        // the blocks of a tipset are all on top of the same parents
+        let parents = self.blocks[0].parents.clone();
+        if self.blocks.iter().any(|block| block.parents != parents) {
             return Err(Error::InvalidTipSet);
         }
 
-        Ok(self
-            .cids
-            .into_iter()
-            .zip(self.blocks.into_iter())
-            .map(|(cid, block)| (cid.inner, block.messages.inner))
-            .collect())
+        Ok(TipSetVote {
+            tipset_key: self.cids.into_iter().map(|cid| cid.inner).collect(),
+            height: self.height,
+            parents: parents.into_iter().map(|cid| cid.inner).collect(),
+            roots: self
+                .blocks
+                .into_iter()
+                .map(|block| BlockRoots {
+                    message_root: block.messages.inner,
+                    state_root: block.parent_state_root.inner,
+                    receipts_root: block.parent_message_receipts.inner,
+                })
+                .collect(),
+        })
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Block {
+    #[serde(rename = "Parents")]
+    pub parents: Vec<Cid>,
     #[serde(rename = "Messages")]
     pub messages: Cid,
+    #[serde(rename = "ParentStateRoot")]
+    pub parent_state_root: Cid,
+    #[serde(rename = "ParentMessageReceipts")]
+    pub parent_message_receipts: Cid,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Cid {
-    #[serde(deserialize_with = "crate::ocw::de::bytes")]
+    /// the binary cid
+    #[serde(deserialize_with = "crate::ocw::de::cid")]
     #[serde(rename = "/")]
     pub inner: Vec<u8>,
 }
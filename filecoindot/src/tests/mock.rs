@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: LGPL-3.0-only
 
 use crate as pallet;
+use crate::VerifiedBlockInfo;
 use frame_support::construct_runtime;
 use frame_support::pallet_prelude::EnsureOrigin;
 #[cfg(test)]
@@ -12,11 +13,15 @@ use sp_core::{
     sr25519::{Public, Signature},
     H256,
 };
+#[cfg(test)]
+use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{testing::Header, traits::IdentityLookup};
 use sp_runtime::{
     testing::TestXt,
     traits::{Extrinsic as ExtrinsicT, Verify},
 };
+#[cfg(test)]
+use std::sync::Arc;
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -27,6 +32,32 @@ pub const RELAYER2: AccountId = Public([3u8; 32]);
 pub const RELAYER3: AccountId = Public([4u8; 32]);
 pub const RELAYER4: AccountId = Public([5u8; 32]);
 
+/// The chain head seen by the relayers, the blocks voted in the tests are confirmed in it
+pub const HEAD: i64 = 50;
+
+/// Block info which only differ by the message root
+pub fn block_info(message_root: Vec<u8>) -> VerifiedBlockInfo {
+    VerifiedBlockInfo {
+        epoch: 1,
+        parents: vec![vec![0, 0]],
+        message_root,
+        state_root: vec![0, 1],
+        receipts_root: vec![0, 1],
+    }
+}
+
+/// The checkpoint the blocks of `block_info` link to
+pub fn checkpoint() -> (Vec<u8>, VerifiedBlockInfo) {
+    (
+        vec![0, 0],
+        VerifiedBlockInfo {
+            epoch: 0,
+            parents: vec![],
+            ..block_info(vec![0, 0])
+        },
+    )
+}
+
 // Configure a mock runtime to test the pallet.
 construct_runtime!(
     pub enum Test where
@@ -35,7 +66,8 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-        FileCoinModule: pallet::{Pallet, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        FileCoinModule: pallet::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
     }
 );
 
@@ -43,6 +75,11 @@ parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const SS58Prefix: u8 = 42;
     pub const OffchainWorkerTimeout: u64 = 1_000_000;
+    pub const ConfirmationDepth: i64 = 2;
+    pub static ExistentialDeposit: u64 = 1;
+    pub const RelayerBond: u64 = 10;
+    pub const RelayerReward: u64 = 5;
+    pub static MaxRelayers: u32 = 20;
 }
 
 /// An implementation of EnsureOrigin
@@ -85,7 +122,7 @@ impl frame_system::Config for Test {
     type BlockLength = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type DbWeight = ();
@@ -95,6 +132,18 @@ impl frame_system::Config for Test {
     type OnSetCode = ();
 }
 
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
 pub type Extrinsic = TestXt<Call, ()>;
 
 impl frame_system::offchain::SigningTypes for Test {
@@ -130,6 +179,13 @@ impl pallet::Config for Test {
     type WeightInfo = ();
     type AuthorityId = pallet::FilecoindotId;
     type OffchainWorkerTimeout = OffchainWorkerTimeout;
+    type ConfirmationDepth = ConfirmationDepth;
+    type Currency = Balances;
+    type RelayerBond = RelayerBond;
+    type Slash = ();
+    type RelayerReward = RelayerReward;
+    type Reward = ();
+    type MaxRelayers = MaxRelayers;
 }
 
 pub struct ExtBuilder {
@@ -155,13 +211,30 @@ impl ExtBuilder {
             .build_storage::<Test>()
             .unwrap();
 
+        pallet_balances::GenesisConfig::<Test> {
+            balances: vec![ALICE, RELAYER1, RELAYER2, RELAYER3, RELAYER4]
+                .into_iter()
+                .map(|account| (account, 100))
+                .collect(),
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
         pallet::GenesisConfig::<Test> {
             vote_threshold: self.vote_threshold,
             vote_period: 1,
-            relayers: Vec::from(RELAYERS.clone()),
+            relayers: self.relayers,
         }
         .assimilate_storage(&mut t)
         .unwrap();
-        t.into()
+
+        let mut ext: sp_io::TestExternalities = t.into();
+        // the benchmarks sign the votes of the relayers with the keystore
+        ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+        ext.execute_with(|| {
+            let (block_cid, info) = checkpoint();
+            FileCoinModule::set_checkpoint(Origin::root(), block_cid, info).unwrap();
+        });
+        ext
     }
 }
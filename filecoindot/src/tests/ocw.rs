@@ -3,23 +3,32 @@
 
 use crate::{
     ocw::{
-        api::{Req, Resp},
+        api::{Endpoint, EndpointConfig, Req, Resp},
         offchain_worker,
-        types::TipSet,
+        result::Error,
+        types::{TipSet, TipSetVote},
+        vote_on_confirmed_tipsets, FILECOIN_RPC, FILECOIN_RPC_QUORUM, LAST_VOTED_EPOCH,
+        SUBMITTED_VOTES,
     },
     tests::mock::*,
-    Relayers,
+    Relayers, TipsetProposalVotes, TipsetVotePayload, VerifiedBlocks,
 };
+use parking_lot::RwLock;
+use serde::Serialize;
 use sp_core::{
-    offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
-    Decode,
+    offchain::{
+        testing::{self, OffchainState, PoolState},
+        OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+    },
+    Decode, Encode,
 };
 use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
-use sp_runtime::{offchain::storage::StorageValueRef, RuntimeAppPublic};
+use sp_runtime::{offchain::storage::StorageValueRef, traits::Verify, RuntimeAppPublic};
 use std::sync::Arc;
 
 const PHRASE: &str = "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
 const FILECOIN_API: &str = "http://filecoin.api";
+const FILECOIN_API_2: &str = "http://filecoin2.api";
 const CHAIN_HEAD_RESP: &[u8] = br#"
 {
   "result": {
@@ -32,28 +41,78 @@ const CHAIN_HEAD_RESP: &[u8] = br#"
     ],
     "Blocks": [
       {
+        "Parents": [
+          { "/": "bafy2bzacea3pdbv5vnyc4m7hkj3x2ifi5urjpuwxxhcg4xqfscq4lnbjoepxe" },
+          { "/": "bafy2bzacedmtaq2ajpd7mxgbrmcmwnqaz2a4cjlpiu5tpxuskn4ynpyddelqs" }
+        ],
         "Messages": {
           "/": "bafy2bzacedhiusftmig7alne5gkuywadhrnnketndnjygr7gdpw4w4cq2u5b2"
+        },
+        "ParentStateRoot": {
+          "/": "bafy2bzacecxzgcxngbetsiyd7ceuk3gzssyd6v4ahbcxifxf3jw6zixnfpdxw"
+        },
+        "ParentMessageReceipts": {
+          "/": "bafy2bzacebwmmdwxvfhyyqp2jtvgkwvdlxiozjxmdskqfeu4mdhk4pqc6fdqm"
         }
       },
       {
+        "Parents": [
+          { "/": "bafy2bzacea3pdbv5vnyc4m7hkj3x2ifi5urjpuwxxhcg4xqfscq4lnbjoepxe" },
+          { "/": "bafy2bzacedmtaq2ajpd7mxgbrmcmwnqaz2a4cjlpiu5tpxuskn4ynpyddelqs" }
+        ],
         "Messages": {
           "/": "bafy2bzacecrt4qybm3klbdri5ofk4lpvnafmo2jc2brpaq25n6pjcr2k3jmcq"
+        },
+        "ParentStateRoot": {
+          "/": "bafy2bzacecxzgcxngbetsiyd7ceuk3gzssyd6v4ahbcxifxf3jw6zixnfpdxw"
+        },
+        "ParentMessageReceipts": {
+          "/": "bafy2bzacebwmmdwxvfhyyqp2jtvgkwvdlxiozjxmdskqfeu4mdhk4pqc6fdqm"
         }
       },
       {
+        "Parents": [
+          { "/": "bafy2bzacea3pdbv5vnyc4m7hkj3x2ifi5urjpuwxxhcg4xqfscq4lnbjoepxe" },
+          { "/": "bafy2bzacedmtaq2ajpd7mxgbrmcmwnqaz2a4cjlpiu5tpxuskn4ynpyddelqs" }
+        ],
         "Messages": {
           "/": "bafy2bzacedjurwnsndbb4zz7kk7lxtrs7g4dxsasjlmggvouhfikug543dnta"
+        },
+        "ParentStateRoot": {
+          "/": "bafy2bzacecxzgcxngbetsiyd7ceuk3gzssyd6v4ahbcxifxf3jw6zixnfpdxw"
+        },
+        "ParentMessageReceipts": {
+          "/": "bafy2bzacebwmmdwxvfhyyqp2jtvgkwvdlxiozjxmdskqfeu4mdhk4pqc6fdqm"
         }
       },
       {
+        "Parents": [
+          { "/": "bafy2bzacea3pdbv5vnyc4m7hkj3x2ifi5urjpuwxxhcg4xqfscq4lnbjoepxe" },
+          { "/": "bafy2bzacedmtaq2ajpd7mxgbrmcmwnqaz2a4cjlpiu5tpxuskn4ynpyddelqs" }
+        ],
         "Messages": {
           "/": "bafy2bzaceba54ejfcbd2cvzqbvfaczbtdot2tfy7l4hlmnn64vxm5fn5bolog"
+        },
+        "ParentStateRoot": {
+          "/": "bafy2bzacecxzgcxngbetsiyd7ceuk3gzssyd6v4ahbcxifxf3jw6zixnfpdxw"
+        },
+        "ParentMessageReceipts": {
+          "/": "bafy2bzacebwmmdwxvfhyyqp2jtvgkwvdlxiozjxmdskqfeu4mdhk4pqc6fdqm"
         }
       },
       {
+        "Parents": [
+          { "/": "bafy2bzacea3pdbv5vnyc4m7hkj3x2ifi5urjpuwxxhcg4xqfscq4lnbjoepxe" },
+          { "/": "bafy2bzacedmtaq2ajpd7mxgbrmcmwnqaz2a4cjlpiu5tpxuskn4ynpyddelqs" }
+        ],
         "Messages": {
           "/": "bafy2bzacebqjfvo2k2x6holp6olhpj7f5wuy35p4mnfzkpqm7agqolpf7qmtg"
+        },
+        "ParentStateRoot": {
+          "/": "bafy2bzacecxzgcxngbetsiyd7ceuk3gzssyd6v4ahbcxifxf3jw6zixnfpdxw"
+        },
+        "ParentMessageReceipts": {
+          "/": "bafy2bzacebwmmdwxvfhyyqp2jtvgkwvdlxiozjxmdskqfeu4mdhk4pqc6fdqm"
         }
       }
     ],
@@ -62,8 +121,113 @@ const CHAIN_HEAD_RESP: &[u8] = br#"
 }
 "#;
 
-#[test]
-fn should_submit_vote_in_ocw() {
+/// The response of `ChainGetTipSetByHeight` for a tipset at `height`, the first
+/// cid is replaced so the tipsets of different heights have different keys
+fn tipset_resp(height: i64) -> String {
+    // the digits of the height spelled with base32 letters
+    let letters = height
+        .to_string()
+        .bytes()
+        .map(|digit| (digit - b'0' + b'a') as char)
+        .collect::<String>();
+    std::str::from_utf8(CHAIN_HEAD_RESP)
+        .unwrap()
+        .replace("1273769", &height.to_string())
+        .replace(
+            "bafy2bzaced43kofq4s4fvsv7esoh2tlst56wngbszkhawfgey4geszwsjj3ww",
+            &format!("bafy{}", letters),
+        )
+}
+
+/// The vote of a relayer for the tipset at `height`
+fn tipset_vote(height: i64) -> TipSetVote {
+    serde_json::from_str::<Resp<TipSet>>(&tipset_resp(height))
+        .unwrap()
+        .result
+        .vote()
+        .unwrap()
+}
+
+/// Expect a request to `endpoint` of the rpc `method` with `params` answered by `response`
+fn expect_request<P: Serialize>(
+    state: &mut OffchainState,
+    endpoint: &Endpoint,
+    method: &'static str,
+    params: P,
+    response: &[u8],
+) {
+    let mut headers = vec![("Content-Type".into(), "application/json".into())];
+    headers.extend(
+        endpoint
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string())),
+    );
+    state.expect_request(testing::PendingRequest {
+        method: "POST".into(),
+        uri: endpoint.url.into(),
+        response: Some(response.to_vec()),
+        sent: true,
+        headers,
+        body: serde_json::to_string(&Req {
+            id: 0,
+            method,
+            jsonrpc: "2.0",
+            params,
+        })
+        .unwrap()
+        .as_bytes()
+        .to_vec(),
+        ..Default::default()
+    });
+}
+
+/// Expect the `ChainGetTipSetByHeight` request at `epoch` answered by the tipset at `height`
+fn expect_tipset_request(state: &mut OffchainState, epoch: u64, height: i64) {
+    expect_request(
+        state,
+        &FILECOIN_API.into(),
+        "Filecoin.ChainGetTipSetByHeight",
+        vec![Some(epoch), None],
+        tipset_resp(height).as_bytes(),
+    );
+}
+
+/// The config of the endpoint at `url` without headers
+fn endpoint_config(url: &str) -> EndpointConfig {
+    EndpointConfig {
+        url: url.as_bytes().to_vec(),
+        headers: vec![],
+    }
+}
+
+/// Use both endpoints with a quorum of 2
+fn set_quorum(second: EndpointConfig) {
+    StorageValueRef::persistent(FILECOIN_RPC).set(&vec![endpoint_config(FILECOIN_API), second]);
+    StorageValueRef::persistent(FILECOIN_RPC_QUORUM).set(&2u32);
+}
+
+/// Run the offchain worker on block 1 with the chain head at 1273769, `last_voted`
+/// is the epoch voted by the relayer in the previous runs, `set_up` is called with
+/// the relayer before the run
+fn run_offchain_worker(
+    last_voted: Option<i64>,
+    expect: impl FnOnce(&mut OffchainState),
+    set_up: impl FnOnce(&AccountId),
+) -> (Arc<RwLock<PoolState>>, Option<i64>, Result<(), String>) {
+    run_ocw(last_voted, expect, set_up, |_| {
+        offchain_worker::<Test>(1u32.into()).map_err(|e| e.to_string())
+    })
+}
+
+/// Same as `run_offchain_worker` with the offchain worker replaced by `run`, which is
+/// called with the relayer
+fn run_ocw(
+    last_voted: Option<i64>,
+    expect: impl FnOnce(&mut OffchainState),
+    set_up: impl FnOnce(&AccountId),
+    run: impl FnOnce(&AccountId) -> Result<(), String>,
+) -> (Arc<RwLock<PoolState>>, Option<i64>, Result<(), String>) {
     let (offchain, state) = testing::TestOffchainExt::new();
     let (pool, pool_state) = testing::TestTransactionPoolExt::new();
 
@@ -79,23 +243,14 @@ fn should_submit_vote_in_ocw() {
     {
         let mut state = state.write();
         let params: Vec<()> = Default::default();
-        state.expect_request(testing::PendingRequest {
-            method: "POST".into(),
-            uri: FILECOIN_API.into(),
-            response: Some(CHAIN_HEAD_RESP.to_vec()),
-            sent: true,
-            headers: vec![("Content-Type".into(), "application/json".into())],
-            body: serde_json::to_string(&Req {
-                id: 0,
-                method: "Filecoin.ChainHead",
-                jsonrpc: "2.0",
-                params,
-            })
-            .unwrap()
-            .as_bytes()
-            .to_vec(),
-            ..Default::default()
-        });
+        expect_request(
+            &mut state,
+            &FILECOIN_API.into(),
+            "Filecoin.ChainHead",
+            params,
+            CHAIN_HEAD_RESP,
+        );
+        expect(&mut state);
     }
 
     // register extensions
@@ -106,31 +261,303 @@ fn should_submit_vote_in_ocw() {
     t.register_extension(KeystoreExt(Arc::new(keystore)));
 
     // execute in test env
-    t.execute_with(|| {
+    let (last_voted, result) = t.execute_with(|| {
         // add inserted key as relayer
         Relayers::<Test>::insert(&relayer, ());
 
+        // the parents of the tipsets are verified
+        for parent in tipset_vote(0).parents {
+            VerifiedBlocks::<Test>::insert(parent, block_info(vec![0, 0]));
+        }
+
         // set rpc endpoint
-        let rpc = StorageValueRef::persistent("FILECOIN_RPC".as_bytes());
-        rpc.set(&vec![FILECOIN_API.as_bytes().to_vec()]);
-
-        // bootstrap ocw on block 1
-        offchain_worker::<Test>(1u32.into()).unwrap();
-
-        // get submited transactions
-        let resp = serde_json::from_slice::<Resp<TipSet>>(CHAIN_HEAD_RESP)
-            .unwrap()
-            .result;
-        for (i, raw_tx) in pool_state.read().transactions.iter().enumerate() {
-            let tx = Extrinsic::decode(&mut &*raw_tx.to_owned()).unwrap();
-            assert_eq!(tx.signature, Some((i as u64, ())));
+        let rpc = StorageValueRef::persistent(FILECOIN_RPC);
+        rpc.set(&vec![endpoint_config(FILECOIN_API)]);
+
+        let epoch = StorageValueRef::persistent(LAST_VOTED_EPOCH);
+        if let Some(last_voted) = last_voted {
+            epoch.set(&last_voted);
+        }
+
+        set_up(&relayer);
+
+        let result = run(&relayer);
+
+        (epoch.get::<i64>().unwrap(), result)
+    });
+
+    (pool_state, last_voted, result)
+}
+
+/// Assert the transaction `tx` is the vote for the tipset at `height` signed by the relayer
+fn assert_tipset_vote(tx: &[u8], height: i64) {
+    let vote = tipset_vote(height);
+    let tx = Extrinsic::decode(&mut &*tx).unwrap();
+    assert_eq!(tx.signature, None);
+    match tx.call {
+        Call::FileCoinModule(crate::Call::submit_tipset_vote_unsigned { payload, signature }) => {
             assert_eq!(
-                tx.call,
-                Call::FileCoinModule(crate::Call::submit_block_vote {
-                    block_cid: resp.cids[i].inner.to_vec(),
-                    message_root_cid: resp.blocks[i].messages.inner.to_vec()
-                })
+                payload,
+                TipsetVotePayload {
+                    tipset_key: vote.tipset_key,
+                    height,
+                    parents: vote.parents,
+                    roots: vote.roots,
+                    head: 1273769,
+                    public: payload.public,
+                }
             );
+            assert!(payload
+                .using_encoded(|payload_bytes| signature.verify(payload_bytes, &payload.public)));
         }
-    });
+        call => panic!("unexpected call {:?}", call),
+    }
+}
+
+#[test]
+fn should_submit_vote_in_ocw() {
+    // the tipset `ConfirmationDepth` epochs below the head
+    let (pool_state, last_voted, result) = run_offchain_worker(
+        None,
+        |state| {
+            expect_tipset_request(state, 1273767, 1273767);
+        },
+        |_| {},
+    );
+
+    assert_eq!(result, Ok(()));
+    let txs = pool_state.read().transactions.clone();
+    assert_eq!(txs.len(), 1);
+    assert_tipset_vote(&txs[0], 1273767);
+    assert_eq!(last_voted, Some(1273767));
+}
+
+#[test]
+fn should_backfill_missed_epochs_in_ocw() {
+    let (pool_state, last_voted, result) = run_offchain_worker(
+        Some(1273764),
+        |state| {
+            // null round, the previous tipset is returned
+            expect_tipset_request(state, 1273765, 1273764);
+            expect_tipset_request(state, 1273766, 1273766);
+            expect_tipset_request(state, 1273767, 1273767);
+        },
+        |_| {},
+    );
+
+    assert_eq!(result, Ok(()));
+    let txs = pool_state.read().transactions.clone();
+    assert_eq!(txs.len(), 2);
+    assert_tipset_vote(&txs[0], 1273766);
+    assert_tipset_vote(&txs[1], 1273767);
+    assert_eq!(last_voted, Some(1273767));
+}
+
+#[test]
+fn should_not_vote_twice_in_ocw() {
+    let (pool_state, last_voted, result) = run_offchain_worker(
+        Some(1273764),
+        |state| {
+            expect_tipset_request(state, 1273765, 1273765);
+            expect_tipset_request(state, 1273766, 1273766);
+            expect_tipset_request(state, 1273767, 1273767);
+        },
+        |relayer| {
+            // voted on chain
+            TipsetProposalVotes::<Test>::insert(
+                tipset_vote(1273765).tipset_key,
+                relayer,
+                vec![block_info(vec![0, 1])],
+            );
+            // submitted in a previous run
+            StorageValueRef::persistent(SUBMITTED_VOTES)
+                .set(&vec![tipset_vote(1273766).tipset_key]);
+            // verified already
+            VerifiedBlocks::<Test>::insert(
+                tipset_vote(1273767).tipset_key[0].clone(),
+                block_info(vec![0, 1]),
+            );
+        },
+    );
+
+    assert_eq!(result, Ok(()));
+    assert!(pool_state.read().transactions.is_empty());
+    assert_eq!(last_voted, Some(1273767));
+}
+
+#[test]
+fn should_not_advance_last_voted_epoch_on_failed_vote_in_ocw() {
+    let (_, last_voted, result) = run_ocw(
+        Some(1273764),
+        |state| {
+            expect_tipset_request(state, 1273765, 1273765);
+            expect_tipset_request(state, 1273766, 1273766);
+        },
+        |_| {},
+        |relayer| {
+            let endpoints: Vec<Endpoint> = vec![FILECOIN_API.into()];
+            vote_on_confirmed_tipsets::<Test, _>(&[relayer.clone()], &endpoints, 1, |vote, _| {
+                if vote.height == 1273766 {
+                    Err(Error::OffchainUnsignedTxError)
+                } else {
+                    Ok(())
+                }
+            })
+            .map_err(|e| e.to_string())
+        },
+    );
+
+    // the failed epoch is voted on again by the next run, the later ones are not
+    // requested before it
+    assert_eq!(result, Ok(()));
+    assert_eq!(last_voted, Some(1273765));
+}
+
+#[test]
+fn should_vote_when_endpoints_reach_quorum_in_ocw() {
+    let (pool_state, last_voted, result) = run_offchain_worker(
+        None,
+        |state| {
+            // the second endpoint is one epoch ahead
+            let params: Vec<()> = Default::default();
+            expect_request(
+                state,
+                &FILECOIN_API_2.into(),
+                "Filecoin.ChainHead",
+                params,
+                tipset_resp(1273770).as_bytes(),
+            );
+            expect_tipset_request(state, 1273767, 1273767);
+            expect_request(
+                state,
+                &FILECOIN_API_2.into(),
+                "Filecoin.ChainGetTipSetByHeight",
+                vec![Some(1273767u64), None],
+                tipset_resp(1273767).as_bytes(),
+            );
+        },
+        |_| set_quorum(endpoint_config(FILECOIN_API_2)),
+    );
+
+    assert_eq!(result, Ok(()));
+    let txs = pool_state.read().transactions.clone();
+    assert_eq!(txs.len(), 1);
+    assert_tipset_vote(&txs[0], 1273767);
+    assert_eq!(last_voted, Some(1273767));
+}
+
+#[test]
+fn should_not_vote_without_quorum_in_ocw() {
+    let (pool_state, last_voted, result) = run_offchain_worker(
+        None,
+        |state| {
+            let params: Vec<()> = Default::default();
+            expect_request(
+                state,
+                &FILECOIN_API_2.into(),
+                "Filecoin.ChainHead",
+                params,
+                CHAIN_HEAD_RESP,
+            );
+            expect_tipset_request(state, 1273767, 1273767);
+            // the second endpoint returns other messages
+            expect_request(
+                state,
+                &FILECOIN_API_2.into(),
+                "Filecoin.ChainGetTipSetByHeight",
+                vec![Some(1273767u64), None],
+                tipset_resp(1273767)
+                    .replace("bafy2bzacedhiusftmig", "bafy2bzacedhiusftmih")
+                    .as_bytes(),
+            );
+        },
+        |_| set_quorum(endpoint_config(FILECOIN_API_2)),
+    );
+
+    assert_eq!(
+        result,
+        Err("not enough filecoin rpc endpoints agree".to_string())
+    );
+    assert!(pool_state.read().transactions.is_empty());
+    assert_eq!(last_voted, None);
+}
+
+#[test]
+fn should_send_endpoint_headers_in_ocw() {
+    let config = EndpointConfig {
+        url: FILECOIN_API_2.as_bytes().to_vec(),
+        headers: vec![(b"Authorization".to_vec(), b"Bearer jwt".to_vec())],
+    };
+    let endpoint = Endpoint::try_from(&config).unwrap();
+    let (pool_state, _, result) = run_offchain_worker(
+        None,
+        |state| {
+            let params: Vec<()> = Default::default();
+            expect_request(
+                state,
+                &endpoint,
+                "Filecoin.ChainHead",
+                params,
+                CHAIN_HEAD_RESP,
+            );
+            expect_tipset_request(state, 1273767, 1273767);
+            expect_request(
+                state,
+                &endpoint,
+                "Filecoin.ChainGetTipSetByHeight",
+                vec![Some(1273767u64), None],
+                tipset_resp(1273767).as_bytes(),
+            );
+        },
+        |_| set_quorum(config.clone()),
+    );
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(pool_state.read().transactions.len(), 1);
+}
+
+#[test]
+fn should_report_rpc_errors_in_ocw() {
+    let (pool_state, last_voted, result) = run_offchain_worker(
+        None,
+        |state| {
+            expect_request(
+                state,
+                &FILECOIN_API.into(),
+                "Filecoin.ChainGetTipSetByHeight",
+                vec![Some(1273767u64), None],
+                br#"{"jsonrpc":"2.0","id":0,"error":{"code":1,"message":"lookback too far"}}"#,
+            );
+        },
+        |_| {},
+    );
+
+    assert_eq!(
+        result,
+        Err("filecoin rpc error 1: lookback too far".to_string())
+    );
+    assert!(pool_state.read().transactions.is_empty());
+    assert_eq!(last_voted, None);
+}
+
+#[test]
+fn should_report_invalid_responses_in_ocw() {
+    let (_, _, result) = run_offchain_worker(
+        None,
+        |state| {
+            expect_request(
+                state,
+                &FILECOIN_API.into(),
+                "Filecoin.ChainGetTipSetByHeight",
+                vec![Some(1273767u64), None],
+                b"Bad Gateway",
+            );
+        },
+        |_| {},
+    );
+
+    assert_eq!(
+        result,
+        Err("failed to parse filecoin rpc response".to_string())
+    );
 }
@@ -1,7 +1,7 @@
 // Copyright 2021 ChainSafe Systems
 // SPDX-License-Identifier: LGPL-3.0-only
 
-use crate::{tests::mock::*, Error, RelayerCount, VoteThreshold};
+use crate::{tests::mock::*, Error, PendingRewards, RelayerBonds, RelayerCount, VoteThreshold};
 use frame_support::{assert_err, assert_ok};
 use sp_runtime::DispatchError::BadOrigin;
 
@@ -73,9 +73,33 @@ fn add_relayer_works() {
         // Dispatch a signed extrinsic.
         assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
         assert_eq!(RelayerCount::<Test>::get(), relayers);
+        assert_eq!(Balances::reserved_balance(RELAYER4), RelayerBond::get());
+        assert_eq!(RelayerBonds::<Test>::get(RELAYER4), RelayerBond::get());
     });
 }
 
+#[test]
+fn add_relayer_fails_insufficient_bond() {
+    let v = ExtBuilder::default();
+    let relayers = v.relayers.len() as u32;
+    v.build().execute_with(|| {
+        let relayer = AccountId::from_raw([9; 32]);
+        assert_err!(
+            FileCoinModule::add_relayer(Origin::signed(ALICE), relayer),
+            Error::<Test>::InsufficientBond
+        );
+        assert_eq!(RelayerCount::<Test>::get(), relayers);
+    });
+}
+
+#[test]
+#[should_panic(expected = "can not reserve the relayer bond")]
+fn genesis_fails_relayer_without_bond() {
+    let mut v = ExtBuilder::default();
+    v.relayers.push(AccountId::from_raw([9; 32]));
+    v.build();
+}
+
 #[test]
 fn add_relayer_fails_already_relayer() {
     let v = ExtBuilder::default();
@@ -88,6 +112,21 @@ fn add_relayer_fails_already_relayer() {
     });
 }
 
+#[test]
+fn add_relayer_fails_too_many_relayers() {
+    let v = ExtBuilder::default();
+    let relayers = v.relayers.len() as u32;
+    v.build().execute_with(|| {
+        MaxRelayers::set(&relayers);
+        assert_err!(
+            FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4),
+            Error::<Test>::TooManyRelayers
+        );
+        assert_eq!(RelayerCount::<Test>::get(), relayers);
+        assert_eq!(Balances::reserved_balance(RELAYER4), 0);
+    });
+}
+
 #[test]
 fn add_relayer_fails_not_admin() {
     ExtBuilder::default().build().execute_with(|| {
@@ -111,6 +150,8 @@ fn remove_relayer_works() {
             RELAYER1
         ));
         assert_eq!(RelayerCount::<Test>::get(), relayers - 1);
+        assert_eq!(Balances::reserved_balance(RELAYER1), 0);
+        assert_eq!(Balances::free_balance(RELAYER1), 100);
     });
 }
 
@@ -141,3 +182,55 @@ fn remove_relayer_fails_not_relayer() {
         assert_eq!(RelayerCount::<Test>::get(), relayers);
     });
 }
+
+#[test]
+fn claim_rewards_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        PendingRewards::<Test>::insert(RELAYER1, RelayerReward::get());
+        assert_ok!(FileCoinModule::claim_rewards(Origin::signed(RELAYER1)));
+
+        System::assert_last_event(Event::FileCoinModule(crate::Event::RewardsClaimed(
+            RELAYER1,
+            RelayerReward::get(),
+        )));
+        assert_eq!(
+            Balances::total_balance(&RELAYER1),
+            100 + RelayerReward::get()
+        );
+        assert_eq!(PendingRewards::<Test>::get(RELAYER1), 0);
+    });
+}
+
+#[test]
+fn claim_rewards_keeps_rewards_below_existential_deposit() {
+    ExtBuilder::default().build().execute_with(|| {
+        let relayer = AccountId::from_raw([9; 32]);
+        ExistentialDeposit::set(&(RelayerReward::get() + 1));
+        PendingRewards::<Test>::insert(relayer, RelayerReward::get());
+        let issuance = Balances::total_issuance();
+
+        assert_err!(
+            FileCoinModule::claim_rewards(Origin::signed(relayer)),
+            Error::<Test>::RewardsBelowExistentialDeposit
+        );
+        assert_eq!(PendingRewards::<Test>::get(relayer), RelayerReward::get());
+        assert_eq!(Balances::total_issuance(), issuance);
+
+        // the rewards are paid out once they can create the account
+        PendingRewards::<Test>::insert(relayer, RelayerReward::get() * 2);
+        assert_ok!(FileCoinModule::claim_rewards(Origin::signed(relayer)));
+        assert_eq!(Balances::total_balance(&relayer), RelayerReward::get() * 2);
+        assert_eq!(PendingRewards::<Test>::get(relayer), 0);
+    });
+}
+
+#[test]
+fn claim_rewards_fails_no_rewards() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::claim_rewards(Origin::signed(RELAYER1)),
+            Error::<Test>::NoRewards
+        );
+    });
+}
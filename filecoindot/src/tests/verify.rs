@@ -1,194 +1,813 @@
 // Copyright 2021 ChainSafe Systems
 // SPDX-License-Identifier: LGPL-3.0-only
 
-use cid::Cid;
 use frame_support::{assert_err, assert_ok};
 
-use crate::{tests::mock::*, Error};
+use crate::{tests::mock::*, ActorState, Error, VerifiedBlockInfo};
 
-pub fn hamt_proof_generation() -> (Vec<Vec<u8>>, Cid) {
-    let (p, cid) = filecoindot_io::benchmarking::hamt_proof_generation();
-    (p, Cid::read_bytes(&*cid).unwrap())
-}
-
-pub fn amt_proof_generation(n: usize) -> (Vec<Vec<u8>>, Cid) {
-    let (p, cid) = filecoindot_io::benchmarking::amt_proof_generation(n as u64);
-    (p, Cid::read_bytes(&*cid).unwrap())
+/// Approve `block_cid` with the votes of all relayers
+fn approve_block(block_cid: &[u8], roots: VerifiedBlockInfo) {
+    for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(relayer),
+            block_cid.to_vec(),
+            roots.clone(),
+            HEAD
+        ));
+    }
 }
 
 #[test]
 fn verify_state_works() {
-    let (proof, cid) = hamt_proof_generation();
+    let (proof, root, address) = filecoindot_io::benchmarking::actor_state_proof_generation(500);
 
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
     ExtBuilder::default().build().execute_with(|| {
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER1),
-            block_cid.clone(),
-            message_cid.clone()
-        ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER2),
-            block_cid.clone(),
-            message_cid.clone()
-        ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER3),
-            block_cid.clone(),
-            message_cid.clone()
-        ));
+        approve_block(&block_cid, roots);
         assert_ok!(FileCoinModule::verify_state_inner(
-            proof,
-            block_cid,
-            cid.to_bytes()
+            proof.clone(),
+            block_cid.clone(),
+            address
         ));
+        // the proof does not lead to another actor
+        let other = filecoindot_io::benchmarking::actor_state_proof_generation(501).2;
+        assert_err!(
+            FileCoinModule::verify_state_inner(proof, block_cid, other),
+            Error::<Test>::VerificationError
+        );
     });
 }
 
 #[test]
 fn verify_state_fails_invalid_block_cid() {
-    let (proof, cid) = hamt_proof_generation();
+    let (proof, root, address) = filecoindot_io::benchmarking::actor_state_proof_generation(500);
 
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
     ExtBuilder::default().build().execute_with(|| {
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER1),
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_state_inner(proof, vec![0, 2], address),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_state_fails_other_root() {
+    let (proof, _, address) = filecoindot_io::benchmarking::actor_state_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, block_info(vec![0, 1]));
+        assert_err!(
+            FileCoinModule::verify_state_inner(proof, block_cid, address),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_receipt_works() {
+    let (proof, root) = filecoindot_io::benchmarking::message_receipt_proof_generation(100);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_receipt_inner(
+            proof.clone(),
             block_cid.clone(),
-            message_cid.clone()
+            100
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER2),
+        // the proof does not lead to another receipt
+        assert_err!(
+            FileCoinModule::verify_receipt_inner(proof, block_cid, 900),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_receipt_fails_invalid_block_cid() {
+    let (proof, root) = filecoindot_io::benchmarking::message_receipt_proof_generation(100);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_receipt_inner(proof, vec![0, 2], 100),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_receipt_fails_other_root() {
+    let (proof, root) = filecoindot_io::benchmarking::message_receipt_proof_generation(100);
+
+    let block_cid = vec![0, 1];
+    // the proof is bound to the receipts root, not any of the other roots
+    let roots = VerifiedBlockInfo {
+        message_root: root.clone(),
+        state_root: root,
+        receipts_root: vec![0, 1],
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_receipt_inner(proof, block_cid, 100),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_works() {
+    let (proof, root, cid) = filecoindot_io::benchmarking::message_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = block_info(root);
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_message_inner(
+            proof.clone(),
             block_cid.clone(),
-            message_cid.clone()
+            true,
+            500,
+            cid.clone()
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER3),
+        // the message is not at another index
+        assert_err!(
+            FileCoinModule::verify_message_inner(
+                proof.clone(),
+                block_cid.clone(),
+                true,
+                501,
+                cid.clone()
+            ),
+            Error::<Test>::VerificationError
+        );
+        // the message is not a BLS message
+        assert_err!(
+            FileCoinModule::verify_message_inner(proof.clone(), block_cid.clone(), false, 500, cid),
+            Error::<Test>::VerificationError
+        );
+        // the proof does not prove another message
+        let other = filecoindot_io::benchmarking::message_proof_generation(501).2;
+        assert_err!(
+            FileCoinModule::verify_message_inner(proof, block_cid, true, 500, other),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_fails() {
+    let (proof, root, cid) = filecoindot_io::benchmarking::message_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = block_info(root);
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_message_inner(proof, vec![0, 2], true, 500, cid),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_fails_other_root() {
+    let (proof, _, cid) = filecoindot_io::benchmarking::message_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, block_info(vec![0, 1]));
+        assert_err!(
+            FileCoinModule::verify_message_inner(proof, block_cid, true, 500, cid),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_state_value_works() {
+    let (proof, root, address) = filecoindot_io::benchmarking::actor_state_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let value =
+            FileCoinModule::verify_state_value_inner(proof.clone(), block_cid.clone(), address)
+                .unwrap();
+        // the state is encoded as `[code, head, nonce, balance]`
+        match serde_cbor::from_slice(&value).unwrap() {
+            serde_cbor::Value::Array(fields) => {
+                assert_eq!(fields.len(), 4);
+                assert_eq!(fields[2], serde_cbor::Value::Integer(500));
+            }
+            _ => panic!("the actor state is not a CBOR array"),
+        }
+        // the proof does not lead to another actor
+        let other = filecoindot_io::benchmarking::actor_state_proof_generation(501).2;
+        assert_err!(
+            FileCoinModule::verify_state_value_inner(proof, block_cid, other),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_receipt_value_works() {
+    let (proof, root) = filecoindot_io::benchmarking::message_receipt_proof_generation(100);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let value =
+            FileCoinModule::verify_receipt_value_inner(proof.clone(), block_cid.clone(), 100)
+                .unwrap();
+        // the receipt is encoded as `[exit_code, return_data, gas_used]`
+        assert_eq!(
+            serde_cbor::from_slice::<serde_cbor::Value>(&value).unwrap(),
+            serde_cbor::Value::Array(vec![
+                serde_cbor::Value::Integer(0),
+                serde_cbor::Value::Bytes(100u64.to_be_bytes().to_vec()),
+                serde_cbor::Value::Integer(100_000),
+            ])
+        );
+        assert_err!(
+            FileCoinModule::verify_receipt_value_inner(proof, block_cid, 900),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_receipt_works() {
+    let (proof, root) = filecoindot_io::benchmarking::message_receipt_proof_generation(100);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let receipt =
+            FileCoinModule::verify_message_receipt_inner(proof.clone(), block_cid.clone(), 100)
+                .unwrap();
+        assert!(receipt.is_success());
+        assert_eq!(receipt.return_data, 100u64.to_be_bytes().to_vec());
+        assert_eq!(receipt.gas_used, 100_000);
+
+        assert_err!(
+            FileCoinModule::verify_message_receipt_inner(proof, block_cid, 101),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_receipt_failed_message() {
+    let (proof, root) = filecoindot_io::benchmarking::message_receipt_proof_generation(101);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let receipt = FileCoinModule::verify_message_receipt_inner(proof, block_cid, 101).unwrap();
+        assert!(!receipt.is_success());
+        assert_eq!(receipt.exit_code, 16);
+    });
+}
+
+#[test]
+fn verify_message_value_works() {
+    let (proof, root, cid) = filecoindot_io::benchmarking::message_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = block_info(root);
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_eq!(
+            FileCoinModule::verify_message_value_inner(proof.clone(), block_cid.clone(), true, 500),
+            Ok(cid)
+        );
+        // the proof is not a proof of the BLS messages
+        assert_err!(
+            FileCoinModule::verify_message_value_inner(proof, block_cid, false, 500),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_value_fails_other_root() {
+    let (proof, root, _) = filecoindot_io::benchmarking::message_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    // the proof is bound to the state root, not the message root
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_message_value_inner(proof, block_cid, true, 500),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_state_exclusion_works() {
+    let (proof, root, address) =
+        filecoindot_io::benchmarking::actor_state_exclusion_proof_generation(1001);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_state_exclusion_inner(
+            proof.clone(),
             block_cid.clone(),
-            message_cid.clone()
+            address
         ));
+        // the actor is in the state
+        let other = filecoindot_io::benchmarking::actor_state_proof_generation(500).2;
         assert_err!(
-            FileCoinModule::verify_state_inner(proof, vec![0, 2], cid.to_bytes()),
+            FileCoinModule::verify_state_exclusion_inner(proof, block_cid, other),
             Error::<Test>::VerificationError
         );
     });
 }
 
 #[test]
-fn verify_receipt_works() {
-    let (proof, cid) = amt_proof_generation(100);
+fn verify_receipt_exclusion_works() {
+    let (proof, root) =
+        filecoindot_io::benchmarking::message_receipt_exclusion_proof_generation(100);
 
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
     ExtBuilder::default().build().execute_with(|| {
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER1),
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_receipt_exclusion_inner(
+            proof.clone(),
             block_cid.clone(),
-            message_cid.clone()
+            100
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER2),
+        assert_err!(
+            FileCoinModule::verify_receipt_exclusion_inner(proof, block_cid, 101),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_exclusion_works() {
+    let (proof, root) = filecoindot_io::benchmarking::message_exclusion_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = block_info(root);
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_message_exclusion_inner(
+            proof.clone(),
             block_cid.clone(),
-            message_cid.clone()
+            true,
+            500
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER3),
+        // the message next to the removed one is in the block
+        assert_err!(
+            FileCoinModule::verify_message_exclusion_inner(proof, block_cid, true, 501),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_message_exclusion_fails_other_root() {
+    let (proof, root) = filecoindot_io::benchmarking::message_exclusion_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_message_exclusion_inner(proof, block_cid, true, 500),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_receipt_exclusion_emits_event() {
+    let (proof, root) =
+        filecoindot_io::benchmarking::message_receipt_exclusion_proof_generation(100);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        receipts_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        // events are not recorded in the genesis block
+        System::set_block_number(1);
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_receipt_exclusion(
+            Origin::signed(ALICE),
+            proof,
             block_cid.clone(),
-            message_cid.clone()
+            100
         ));
-        assert_ok!(FileCoinModule::verify_receipt_inner(
-            proof,
-            block_cid,
-            cid.to_bytes()
+        System::assert_last_event(Event::FileCoinModule(
+            crate::Event::ReceiptExclusionVerified(block_cid, 100),
         ));
     });
 }
 
 #[test]
-fn verify_receipt_fails_invalid_block_cid() {
-    let (proof, cid) = amt_proof_generation(100);
+fn verify_state_exclusion_emits_event() {
+    let (proof, root, address) =
+        filecoindot_io::benchmarking::actor_state_exclusion_proof_generation(1001);
 
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
     ExtBuilder::default().build().execute_with(|| {
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER1),
+        System::set_block_number(1);
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_state_exclusion(
+            Origin::signed(ALICE),
+            proof,
             block_cid.clone(),
-            message_cid.clone()
+            address.clone()
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER2),
+        System::assert_last_event(Event::FileCoinModule(crate::Event::StateExclusionVerified(
+            block_cid, address,
+        )));
+    });
+}
+
+#[test]
+fn verify_message_exclusion_emits_event() {
+    let (proof, root) = filecoindot_io::benchmarking::message_exclusion_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        approve_block(&block_cid, block_info(root));
+        assert_ok!(FileCoinModule::verify_message_exclusion(
+            Origin::signed(ALICE),
+            proof,
             block_cid.clone(),
-            message_cid.clone()
+            true,
+            500
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER3),
-            block_cid.clone(),
-            message_cid.clone()
+        System::assert_last_event(Event::FileCoinModule(
+            crate::Event::MessageExclusionVerified(block_cid, true, 500),
         ));
+    });
+}
+
+#[test]
+fn verify_message_exclusion_fails_unverified_block() {
+    let (proof, _) = filecoindot_io::benchmarking::message_exclusion_proof_generation(500);
+
+    ExtBuilder::default().build().execute_with(|| {
         assert_err!(
-            FileCoinModule::verify_receipt_inner(proof, vec![0, 2], cid.to_bytes()),
+            FileCoinModule::verify_message_exclusion(
+                Origin::signed(ALICE),
+                proof,
+                vec![0, 1],
+                true,
+                500
+            ),
             Error::<Test>::VerificationError
         );
     });
 }
 
+/// The state of the actor `id` generated by `actor_state_proof_generation`
+fn actor_state(id: u64) -> ActorState {
+    ActorState {
+        code: cid::new_from_cbor(&[1], cid::Code::Blake2b256).to_bytes(),
+        head: cid::new_from_cbor(&[2], cid::Code::Blake2b256).to_bytes(),
+        nonce: id,
+        balance: id as u128,
+    }
+}
+
 #[test]
-fn verify_message_works() {
-    let (proof, cid) = hamt_proof_generation();
+fn verify_actor_state_works() {
+    let (proof, root, address) = filecoindot_io::benchmarking::actor_state_proof_generation(500);
 
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
     ExtBuilder::default().build().execute_with(|| {
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER1),
-            block_cid.clone(),
-            message_cid.clone()
-        ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER2),
+        // events are not recorded in the genesis block
+        System::set_block_number(1);
+        approve_block(&block_cid, roots);
+        assert_ok!(FileCoinModule::verify_actor_state(
+            Origin::signed(ALICE),
+            proof,
             block_cid.clone(),
-            message_cid.clone()
+            address.clone(),
+            actor_state(500)
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER3),
+        System::assert_last_event(Event::FileCoinModule(crate::Event::ActorStateVerified(
+            block_cid, address, 500, 500,
+        )));
+    });
+}
+
+#[test]
+fn verify_actor_state_fails_other_state() {
+    let (proof, root, address) = filecoindot_io::benchmarking::actor_state_proof_generation(500);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let state = ActorState {
+            balance: 1_000_000,
+            ..actor_state(500)
+        };
+        assert_err!(
+            FileCoinModule::verify_actor_state_inner(proof, block_cid, address, &state),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_deal_works() {
+    let (state_proof, market_state, deal_proof, root) =
+        filecoindot_io::benchmarking::deal_proof_generation(42);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let deal = FileCoinModule::verify_deal_inner(
             block_cid.clone(),
-            message_cid.clone()
-        ));
-        assert_ok!(FileCoinModule::verify_message_inner(
-            proof,
-            block_cid,
-            cid.to_bytes()
-        ));
+            42,
+            state_proof.clone(),
+            market_state.clone(),
+            deal_proof.clone(),
+        )
+        .unwrap();
+        assert_eq!(deal.start_epoch, 42);
+        assert_eq!(deal.end_epoch, 142);
+
+        // the proof does not prove another deal
+        assert_err!(
+            FileCoinModule::verify_deal_inner(block_cid, 43, state_proof, market_state, deal_proof),
+            Error::<Test>::VerificationError
+        );
     });
 }
 
 #[test]
-fn verify_message_fails() {
-    let (proof, cid) = hamt_proof_generation();
+fn verify_deal_fails_invalid_block_cid() {
+    let (state_proof, market_state, deal_proof, root) =
+        filecoindot_io::benchmarking::deal_proof_generation(42);
 
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
     ExtBuilder::default().build().execute_with(|| {
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER1),
+        approve_block(&block_cid, roots);
+        assert_err!(
+            FileCoinModule::verify_deal_inner(
+                vec![0, 2],
+                42,
+                state_proof,
+                market_state,
+                deal_proof
+            ),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+#[test]
+fn verify_sector_works() {
+    let (miner, state_proof, miner_state, sector_proof, root) =
+        filecoindot_io::benchmarking::sector_proof_generation(7);
+
+    let block_cid = vec![0, 1];
+    let roots = VerifiedBlockInfo {
+        state_root: root,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        approve_block(&block_cid, roots);
+        let sector = FileCoinModule::verify_sector_inner(
             block_cid.clone(),
-            message_cid.clone()
-        ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER2),
+            miner.clone(),
+            7,
+            state_proof.clone(),
+            miner_state.clone(),
+            sector_proof.clone(),
+        )
+        .unwrap();
+        assert_eq!(sector.sector_number, 7);
+        assert_eq!(sector.deal_ids, vec![7]);
+        assert_eq!(sector.expiration, 1007);
+
+        // the proof does not prove another sector
+        assert_err!(
+            FileCoinModule::verify_sector_inner(
+                block_cid,
+                miner,
+                8,
+                state_proof,
+                miner_state,
+                sector_proof
+            ),
+            Error::<Test>::VerificationError
+        );
+    });
+}
+
+/// Generate `n` linked headers from the newest to the oldest, returns the headers and
+/// the cid of the newest one
+fn ancestry_headers(n: i64) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let (_, root) = filecoindot_io::benchmarking::message_receipt_exclusion_proof_generation(1);
+    let mut headers = vec![];
+    let mut parent = root.clone();
+    for epoch in 1..=n {
+        let header = filecoindot_io::benchmarking::block_header_generation(
+            epoch,
+            vec![parent],
+            root.clone(),
+        );
+        parent = filecoindot_io::forest_proof_verify::decode_block_header(header.clone())
+            .unwrap()
+            .0;
+        headers.push(header);
+    }
+    headers.reverse();
+    (headers, parent)
+}
+
+#[test]
+fn verify_ancestry_works() {
+    let (headers, newest) = ancestry_headers(5);
+    let block_cid = vec![0, 1];
+    let info = VerifiedBlockInfo {
+        epoch: 6,
+        parents: vec![newest],
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
             block_cid.clone(),
-            message_cid.clone()
+            info
         ));
-        assert_ok!(FileCoinModule::submit_block_vote(
-            Origin::signed(RELAYER3),
+
+        let ancestor =
+            FileCoinModule::verify_ancestry_inner(block_cid.clone(), headers.clone()).unwrap();
+        for (epoch, header) in (1..=5).rev().zip(headers) {
+            let (cid, info) = FileCoinModule::decode_block_header(header).unwrap();
+            assert_eq!(FileCoinModule::verified_block(&cid), Some(info));
+            assert_eq!(FileCoinModule::verified_blocks_at_height(epoch), vec![cid]);
+        }
+        assert_eq!(FileCoinModule::verified_blocks_at_height(1), vec![ancestor]);
+    });
+}
+
+#[test]
+fn verify_ancestry_fails_broken_link() {
+    let (mut headers, newest) = ancestry_headers(5);
+    let block_cid = vec![0, 1];
+    let info = VerifiedBlockInfo {
+        epoch: 6,
+        parents: vec![newest],
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
             block_cid.clone(),
-            message_cid.clone()
+            info
         ));
+
+        // the block is not verified
         assert_err!(
-            FileCoinModule::verify_message_inner(proof, vec![0, 2], cid.to_bytes()),
+            FileCoinModule::verify_ancestry_inner(vec![0, 2], headers.clone()),
             Error::<Test>::VerificationError
         );
+        // the headers do not start from a parent of the block
+        assert_err!(
+            FileCoinModule::verify_ancestry_inner(block_cid.clone(), headers[1..].to_vec()),
+            Error::<Test>::InvalidAncestry
+        );
+        // a header is missing in the middle of the chain, nothing is verified
+        let removed = headers.remove(2);
+        assert_err!(
+            FileCoinModule::verify_ancestry_inner(block_cid.clone(), headers.clone()),
+            Error::<Test>::InvalidAncestry
+        );
+        let (cid, _) = FileCoinModule::decode_block_header(headers[0].clone()).unwrap();
+        assert!(FileCoinModule::verified_block(&cid).is_none());
+        assert_err!(
+            FileCoinModule::verify_ancestry_inner(block_cid, vec![removed]),
+            Error::<Test>::InvalidAncestry
+        );
+    });
+}
+
+#[test]
+fn verify_ancestry_fails_forked_ancestor() {
+    let (headers, newest) = ancestry_headers(5);
+    let block_cid = vec![0, 1];
+    let info = VerifiedBlockInfo {
+        epoch: 6,
+        parents: vec![newest],
+        ..block_info(vec![0, 1])
+    };
+    let forked = VerifiedBlockInfo {
+        epoch: 3,
+        parents: vec![vec![9, 9]],
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![0, 3],
+            forked
+        ));
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            block_cid.clone(),
+            info
+        ));
+
+        // the ancestor at epoch 3 conflicts with the verified block of epoch 3
+        assert_err!(
+            FileCoinModule::verify_ancestry_inner(block_cid.clone(), headers.clone()),
+            Error::<Test>::AncestorForked
+        );
+        let (cid, _) = FileCoinModule::decode_block_header(headers[0].clone()).unwrap();
+        assert!(FileCoinModule::verified_block(&cid).is_none());
+        // the ancestors above the fork are still verifiable
+        assert_ok!(FileCoinModule::verify_ancestry_inner(
+            block_cid,
+            headers[..2].to_vec()
+        ));
     });
 }
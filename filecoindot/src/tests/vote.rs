@@ -3,25 +3,36 @@
 
 use frame_support::pallet_prelude::EnsureOrigin;
 use frame_support::{assert_err, assert_ok};
-use sp_runtime::DispatchError::BadOrigin;
+use sp_core::{
+    sr25519::{Pair, Signature},
+    Encode, Pair as _,
+};
+use sp_runtime::{
+    traits::ValidateUnsigned,
+    transaction_validity::{InvalidTransaction, TransactionSource},
+    DispatchError::BadOrigin,
+};
 
 use crate::types::{EnsureRelayer, ProposalStatus};
 use crate::{
-    tests::mock::*, BlockSubmissionProposals, Error, MessageRootCidCounter, VerifiedBlocks,
+    tests::mock::*, BlockRoots, BlockSubmissionProposals, Checkpoint, Error, MessageRootCidCounter,
+    PendingRewards, RelayerBonds, RelayerCount, Relayers, TipsetProposalVotes,
+    TipsetSubmissionProposals, TipsetVotePayload, VerifiedBlockInfo, VerifiedBlocks, VoteThreshold,
 };
 
 #[test]
 fn submit_block_vote_works() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_eq!(
-            MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).unwrap(),
+            MessageRootCidCounter::<Test>::get(&block_cid, &roots).unwrap(),
             1
         );
 
@@ -30,43 +41,118 @@ fn submit_block_vote_works() {
     });
 }
 
+#[test]
+fn submit_block_vote_counts_string_cids_as_binary_cids() {
+    let cid = "bafy2bzaced43kofq4s4fvsv7esoh2tlst56wngbszkhawfgey4geszwsjj3ww";
+    let binary = crate::ocw::de::decode_cid(cid).unwrap();
+    let roots = block_info(binary.clone());
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER1),
+            cid.as_bytes().to_vec(),
+            block_info(cid.as_bytes().to_vec()),
+            HEAD
+        ));
+        for relayer in [RELAYER2, RELAYER3] {
+            assert_ok!(FileCoinModule::submit_block_vote(
+                Origin::signed(relayer),
+                binary.clone(),
+                roots.clone(),
+                HEAD
+            ));
+        }
+
+        // the votes are counted for the binary cids, no relayer is slashed
+        assert_eq!(VerifiedBlocks::<Test>::get(&binary), Some(roots));
+        assert!(VerifiedBlocks::<Test>::get(cid.as_bytes()).is_none());
+        for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+            assert_eq!(PendingRewards::<Test>::get(relayer), RelayerReward::get());
+        }
+    });
+}
+
 #[test]
 fn submit_block_vote_fails_not_relayer() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_err!(
             FileCoinModule::submit_block_vote(
                 Origin::signed(ALICE),
                 block_cid.clone(),
-                message_cid.clone()
+                roots.clone(),
+                HEAD
             ),
             Error::<Test>::NotRelayer
         );
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).is_none(),);
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &roots).is_none(),);
+    });
+}
+
+#[test]
+fn submit_block_vote_fails_not_confirmed() {
+    let block_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_block_vote(
+                Origin::signed(RELAYER1),
+                block_cid.clone(),
+                roots.clone(),
+                2
+            ),
+            Error::<Test>::TipsetNotConfirmed
+        );
+        assert!(!BlockSubmissionProposals::<Test>::contains_key(&block_cid));
+
+        // the head reported by a relayer is only trusted for its own vote
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER1),
+            block_cid.clone(),
+            roots.clone(),
+            i64::MAX
+        ));
+        for relayer in [RELAYER2, RELAYER3] {
+            assert_err!(
+                FileCoinModule::submit_block_vote(
+                    Origin::signed(relayer),
+                    block_cid.clone(),
+                    roots.clone(),
+                    2
+                ),
+                Error::<Test>::TipsetNotConfirmed
+            );
+        }
+        assert_eq!(
+            MessageRootCidCounter::<Test>::get(&block_cid, &roots),
+            Some(1)
+        );
+        assert!(!VerifiedBlocks::<Test>::contains_key(&block_cid));
     });
 }
 
 #[test]
 fn submit_block_vote_fails_already_voted() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_err!(
             FileCoinModule::submit_block_vote(
                 Origin::signed(RELAYER1),
                 block_cid.clone(),
-                message_cid.clone()
+                roots.clone(),
+                HEAD
             ),
             Error::<Test>::AlreadyVoted
         );
         assert_eq!(
-            MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).unwrap(),
+            MessageRootCidCounter::<Test>::get(&block_cid, &roots).unwrap(),
             1
         );
     });
@@ -75,74 +161,187 @@ fn submit_block_vote_fails_already_voted() {
 #[test]
 fn submit_block_vote_resolve_rejected() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         System::set_block_number(100);
         assert_err!(
             FileCoinModule::submit_block_vote(
                 Origin::signed(RELAYER2),
                 block_cid.clone(),
-                message_cid.clone()
+                roots.clone(),
+                HEAD
             ),
             Error::<Test>::ProposalExpired
         );
         assert!(!VerifiedBlocks::<Test>::contains_key(&block_cid));
         assert!(BlockSubmissionProposals::<Test>::get(&block_cid).is_none(),);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).is_none(),);
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &roots).is_none(),);
     });
 }
 
 #[test]
 fn submit_block_vote_resolve_approved() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER2),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER3),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         // assert_eq!(*p.get_status(), ProposalStatus::Approved);
-        assert!(VerifiedBlocks::<Test>::contains_key(&block_cid));
+        assert_eq!(VerifiedBlocks::<Test>::get(&block_cid), Some(roots.clone()));
         // assert_eq!(BlockSubmissionProposals::<Test>::get(&block_cid).is_none(), true);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).is_none(),);
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &roots).is_none(),);
+    });
+}
+
+#[test]
+fn submit_block_vote_slashes_other_info() {
+    let block_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER4),
+            block_cid.clone(),
+            block_info(vec![0, 2]),
+            HEAD
+        ));
+        for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+            assert_ok!(FileCoinModule::submit_block_vote(
+                Origin::signed(relayer),
+                block_cid.clone(),
+                roots.clone(),
+                HEAD
+            ));
+        }
+        assert_eq!(VerifiedBlocks::<Test>::get(&block_cid), Some(roots));
+
+        System::assert_has_event(Event::FileCoinModule(crate::Event::RelayerSlashed(
+            RELAYER4,
+            RelayerBond::get(),
+        )));
+        assert_eq!(Balances::reserved_balance(RELAYER4), 0);
+        assert_eq!(Balances::total_balance(&RELAYER4), 100 - RelayerBond::get());
+        assert_eq!(RelayerBonds::<Test>::get(RELAYER4), 0);
+        assert_eq!(PendingRewards::<Test>::get(RELAYER4), 0);
+        // the relayer has to be added back with a new bond
+        System::assert_has_event(Event::FileCoinModule(crate::Event::RelayerRemoved(
+            RELAYER4,
+        )));
+        assert!(!Relayers::<Test>::contains_key(RELAYER4));
+        assert_eq!(RelayerCount::<Test>::get(), 3);
+        // the relayers who voted for the approved info keep their bond and are rewarded
+        assert_eq!(Balances::reserved_balance(RELAYER1), RelayerBond::get());
+        assert_eq!(PendingRewards::<Test>::get(RELAYER1), RelayerReward::get());
+    });
+}
+
+#[test]
+fn submit_block_vote_keeps_slashed_relayer_at_threshold() {
+    let (first_cid, second_cid) = (vec![0, 1], vec![0, 2]);
+    let (first, second) = (block_info(vec![0, 1]), block_info(vec![0, 2]));
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
+        // RELAYER4 votes for the second block before being removed
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER3),
+            second_cid.clone(),
+            block_info(vec![0, 3]),
+            HEAD
+        ));
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER4),
+            second_cid.clone(),
+            second.clone(),
+            HEAD
+        ));
+
+        // the relayer count drops to the threshold
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER4),
+            first_cid.clone(),
+            block_info(vec![0, 3]),
+            HEAD
+        ));
+        for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+            assert_ok!(FileCoinModule::submit_block_vote(
+                Origin::signed(relayer),
+                first_cid.clone(),
+                first.clone(),
+                HEAD
+            ));
+        }
+        assert_eq!(VerifiedBlocks::<Test>::get(&first_cid), Some(first));
+        assert!(!Relayers::<Test>::contains_key(RELAYER4));
+        assert_eq!(RelayerCount::<Test>::get(), VoteThreshold::<Test>::get());
+
+        // the relayer count cannot drop below the threshold, the relayer is slashed
+        // and kept without bond
+        for relayer in [RELAYER1, RELAYER2] {
+            assert_ok!(FileCoinModule::submit_block_vote(
+                Origin::signed(relayer),
+                second_cid.clone(),
+                second.clone(),
+                HEAD
+            ));
+        }
+        assert_eq!(VerifiedBlocks::<Test>::get(&second_cid), Some(second));
+        System::assert_has_event(Event::FileCoinModule(crate::Event::RelayerSlashed(
+            RELAYER3,
+            RelayerBond::get(),
+        )));
+        assert_eq!(Balances::reserved_balance(RELAYER3), 0);
+        assert_eq!(RelayerBonds::<Test>::get(RELAYER3), 0);
+        assert!(Relayers::<Test>::contains_key(RELAYER3));
+        assert_eq!(RelayerCount::<Test>::get(), VoteThreshold::<Test>::get());
     });
 }
 
 #[test]
 fn submit_block_vote_resolve_completed() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER2),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER3),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         System::set_block_number(100);
         assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
@@ -150,35 +349,39 @@ fn submit_block_vote_resolve_completed() {
             FileCoinModule::submit_block_vote(
                 Origin::signed(RELAYER4),
                 block_cid.clone(),
-                message_cid.clone()
+                roots.clone(),
+                HEAD
             ),
             Error::<Test>::BlockAlreadyVerified
         );
         assert!(VerifiedBlocks::<Test>::contains_key(&block_cid));
         assert!(BlockSubmissionProposals::<Test>::get(&block_cid).is_none(),);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).is_none(),);
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &roots).is_none(),);
     });
 }
 
 #[test]
 fn close_block_proposal_already_verified() {
     let block_cid = vec![0, 1];
-    let message_cid = vec![0, 1];
+    let roots = block_info(vec![0, 1]);
     ExtBuilder::default().build().execute_with(|| {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER2),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER3),
             block_cid.clone(),
-            message_cid.clone()
+            roots.clone(),
+            HEAD
         ));
 
         System::set_block_number(100);
@@ -188,7 +391,7 @@ fn close_block_proposal_already_verified() {
         );
         assert!(VerifiedBlocks::<Test>::contains_key(&block_cid));
         assert!(BlockSubmissionProposals::<Test>::get(&block_cid).is_none(),);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &message_cid).is_none(),);
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &roots).is_none(),);
     });
 }
 
@@ -210,17 +413,20 @@ fn close_block_proposal_works() {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            vec![0, 1]
+            block_info(vec![0, 1]),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER2),
             block_cid.clone(),
-            vec![0, 2]
+            block_info(vec![0, 2]),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER3),
             block_cid.clone(),
-            vec![0, 3]
+            block_info(vec![0, 3]),
+            HEAD
         ));
         System::set_block_number(100);
         assert_ok!(FileCoinModule::close_block_proposal(
@@ -233,9 +439,9 @@ fn close_block_proposal_works() {
             false
         );
         assert!(BlockSubmissionProposals::<Test>::get(&block_cid).is_none(),);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &vec![0, 1]).is_none(),);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &vec![0, 2]).is_none(),);
-        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &vec![0, 3]).is_none(),);
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &block_info(vec![0, 1])).is_none());
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &block_info(vec![0, 2])).is_none());
+        assert!(MessageRootCidCounter::<Test>::get(&block_cid, &block_info(vec![0, 3])).is_none());
     });
 }
 
@@ -246,17 +452,20 @@ fn close_block_proposal_no_effect() {
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER1),
             block_cid.clone(),
-            vec![0, 1]
+            block_info(vec![0, 1]),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER2),
             block_cid.clone(),
-            vec![0, 2]
+            block_info(vec![0, 2]),
+            HEAD
         ));
         assert_ok!(FileCoinModule::submit_block_vote(
             Origin::signed(RELAYER3),
             block_cid.clone(),
-            vec![0, 3]
+            block_info(vec![0, 3]),
+            HEAD
         ));
         assert_ok!(FileCoinModule::close_block_proposal(
             Origin::signed(ALICE),
@@ -270,15 +479,15 @@ fn close_block_proposal_no_effect() {
             &ProposalStatus::Active
         );
         assert_eq!(
-            MessageRootCidCounter::<Test>::get(&block_cid, &vec![0, 1]).unwrap(),
+            MessageRootCidCounter::<Test>::get(&block_cid, &block_info(vec![0, 1])).unwrap(),
             1
         );
         assert_eq!(
-            MessageRootCidCounter::<Test>::get(&block_cid, &vec![0, 2]).unwrap(),
+            MessageRootCidCounter::<Test>::get(&block_cid, &block_info(vec![0, 2])).unwrap(),
             1
         );
         assert_eq!(
-            MessageRootCidCounter::<Test>::get(&block_cid, &vec![0, 3]).unwrap(),
+            MessageRootCidCounter::<Test>::get(&block_cid, &block_info(vec![0, 3])).unwrap(),
             1
         );
     });
@@ -291,3 +500,747 @@ fn ensure_relayer_works() {
         assert!(EnsureRelayer::<Test>::try_origin(Origin::signed(ALICE)).is_err(),);
     });
 }
+
+#[test]
+fn submit_block_vote_indexes_verified_blocks_by_height() {
+    let block_a = vec![0, 1];
+    let block_b = vec![0, 2];
+    let info = VerifiedBlockInfo {
+        epoch: 42,
+        ..block_info(vec![0, 1])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        for block_cid in [&block_a, &block_b] {
+            for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+                assert_ok!(FileCoinModule::submit_block_vote(
+                    Origin::signed(relayer),
+                    block_cid.clone(),
+                    info.clone(),
+                    HEAD
+                ));
+            }
+        }
+
+        assert_eq!(FileCoinModule::verified_block(&block_a), Some(info.clone()));
+        assert_eq!(
+            FileCoinModule::verified_blocks_at_height(42),
+            vec![block_a, block_b]
+        );
+        assert!(FileCoinModule::verified_blocks_at_height(1).is_empty());
+    });
+}
+
+#[test]
+fn submit_block_vote_counts_epoch_and_parents() {
+    let block_cid = vec![0, 1];
+    let info = block_info(vec![0, 1]);
+    ExtBuilder::default().build().execute_with(|| {
+        let (_, checkpoint_info) = checkpoint();
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![0, 2],
+            checkpoint_info
+        ));
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER1),
+            block_cid.clone(),
+            info.clone(),
+            HEAD
+        ));
+        // the same roots with other parents are a different vote
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER2),
+            block_cid.clone(),
+            VerifiedBlockInfo {
+                parents: vec![vec![0, 2]],
+                ..info.clone()
+            },
+            HEAD
+        ));
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER3),
+            block_cid.clone(),
+            VerifiedBlockInfo {
+                epoch: 2,
+                ..info.clone()
+            },
+            HEAD
+        ));
+
+        assert!(!VerifiedBlocks::<Test>::contains_key(&block_cid));
+        assert_eq!(
+            MessageRootCidCounter::<Test>::get(&block_cid, &info).unwrap(),
+            1
+        );
+    });
+}
+
+#[test]
+fn submit_block_header_works() {
+    let (_, root) = filecoindot_io::benchmarking::message_receipt_exclusion_proof_generation(1);
+    let (_, parent) = filecoindot_io::benchmarking::message_receipt_exclusion_proof_generation(2);
+    let header = filecoindot_io::benchmarking::block_header_generation(
+        42,
+        vec![parent.clone()],
+        root.clone(),
+    );
+    ExtBuilder::default().build().execute_with(|| {
+        let (_, checkpoint_info) = checkpoint();
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            parent.clone(),
+            checkpoint_info
+        ));
+        let (block_cid, info) = FileCoinModule::decode_block_header(header.clone()).unwrap();
+        assert_eq!(
+            info,
+            VerifiedBlockInfo {
+                epoch: 42,
+                parents: vec![parent],
+                message_root: root.clone(),
+                state_root: root.clone(),
+                receipts_root: root,
+            }
+        );
+
+        for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+            assert_ok!(FileCoinModule::submit_block_header(
+                Origin::signed(relayer),
+                header.clone(),
+                HEAD
+            ));
+        }
+        assert_eq!(VerifiedBlocks::<Test>::get(&block_cid), Some(info));
+        assert_eq!(
+            FileCoinModule::verified_blocks_at_height(42),
+            vec![block_cid]
+        );
+    });
+}
+
+#[test]
+fn submit_block_header_fails_invalid_header() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_block_header(Origin::signed(RELAYER1), vec![0, 1], HEAD),
+            Error::<Test>::InvalidBlockHeader
+        );
+    });
+}
+
+#[test]
+fn set_checkpoint_works() {
+    let info = VerifiedBlockInfo {
+        epoch: 100,
+        parents: vec![],
+        ..block_info(vec![1, 0])
+    };
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::set_checkpoint(Origin::signed(RELAYER1), vec![1, 0], info.clone()),
+            BadOrigin
+        );
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![1, 0],
+            info.clone()
+        ));
+        assert_eq!(Checkpoint::<Test>::get(), Some(vec![1, 0]));
+        assert_eq!(FileCoinModule::verified_block(&[1, 0]), Some(info.clone()));
+        assert_eq!(
+            FileCoinModule::verified_blocks_at_height(100),
+            vec![vec![1, 0]]
+        );
+        assert_err!(
+            FileCoinModule::set_checkpoint(Origin::root(), vec![1, 0], info),
+            Error::<Test>::BlockAlreadyVerified
+        );
+    });
+}
+
+#[test]
+fn submit_block_vote_fails_unknown_parent() {
+    let block_cid = vec![0, 1];
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_block_vote(
+                Origin::signed(RELAYER1),
+                block_cid.clone(),
+                VerifiedBlockInfo {
+                    parents: vec![vec![0, 2]],
+                    ..block_info(vec![0, 1])
+                },
+                HEAD
+            ),
+            Error::<Test>::UnknownParent
+        );
+        assert_err!(
+            FileCoinModule::submit_block_vote(
+                Origin::signed(RELAYER1),
+                block_cid,
+                VerifiedBlockInfo {
+                    parents: vec![],
+                    ..block_info(vec![0, 1])
+                },
+                HEAD
+            ),
+            Error::<Test>::UnknownParent
+        );
+    });
+}
+
+#[test]
+fn submit_block_vote_fails_epoch_before_parents() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_block_vote(
+                Origin::signed(RELAYER1),
+                vec![0, 1],
+                VerifiedBlockInfo {
+                    epoch: 0,
+                    ..block_info(vec![0, 1])
+                },
+                HEAD
+            ),
+            Error::<Test>::InvalidEpoch
+        );
+    });
+}
+
+#[test]
+fn submit_block_vote_builds_on_verified_blocks() {
+    ExtBuilder::default().build().execute_with(|| {
+        approve(vec![0, 1], block_info(vec![0, 1]));
+        let child = VerifiedBlockInfo {
+            epoch: 2,
+            parents: vec![vec![0, 1]],
+            ..block_info(vec![0, 2])
+        };
+        approve(vec![0, 2], child.clone());
+        assert_eq!(FileCoinModule::verified_block(&[0, 2]), Some(child));
+    });
+}
+
+#[test]
+fn submit_block_vote_drops_fork() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let (_, checkpoint_info) = checkpoint();
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![0, 9],
+            checkpoint_info
+        ));
+
+        approve(vec![0, 1], block_info(vec![0, 1]));
+        // another block of the same epoch on top of the other checkpoint
+        let fork = VerifiedBlockInfo {
+            parents: vec![vec![0, 9]],
+            ..block_info(vec![0, 2])
+        };
+        approve(vec![0, 2], fork);
+
+        System::assert_last_event(Event::FileCoinModule(crate::Event::ForkDetected(
+            1,
+            vec![0, 1],
+            vec![0, 2],
+        )));
+        assert!(!VerifiedBlocks::<Test>::contains_key(&vec![0, 2]));
+        assert!(BlockSubmissionProposals::<Test>::get(&vec![0, 2]).is_none());
+        assert_eq!(
+            FileCoinModule::verified_blocks_at_height(1),
+            vec![vec![0, 1]]
+        );
+
+        // a block of the same tipset is not a fork
+        approve(vec![0, 3], block_info(vec![0, 3]));
+        assert_eq!(
+            FileCoinModule::verified_blocks_at_height(1),
+            vec![vec![0, 1], vec![0, 3]]
+        );
+    });
+}
+
+#[test]
+fn submit_block_vote_does_not_slash_fork() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (_, checkpoint_info) = checkpoint();
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![0, 9],
+            checkpoint_info
+        ));
+        approve(vec![0, 1], block_info(vec![0, 1]));
+
+        let fork = VerifiedBlockInfo {
+            parents: vec![vec![0, 9]],
+            ..block_info(vec![0, 2])
+        };
+        assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(RELAYER4),
+            vec![0, 2],
+            block_info(vec![0, 2]),
+            HEAD
+        ));
+        approve(vec![0, 2], fork);
+
+        // the fork is dropped, the votes for it are not settled
+        assert!(!VerifiedBlocks::<Test>::contains_key(&vec![0, 2]));
+        // the relayers are only rewarded for the block of the verified chain
+        assert_eq!(PendingRewards::<Test>::get(RELAYER2), RelayerReward::get());
+        assert!(Relayers::<Test>::contains_key(RELAYER4));
+        assert_eq!(RelayerBonds::<Test>::get(RELAYER4), RelayerBond::get());
+        assert_eq!(Balances::reserved_balance(RELAYER4), RelayerBond::get());
+    });
+}
+
+#[test]
+fn submit_tipset_vote_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let tipset_key = vec![vec![0, 1], vec![0, 2]];
+        approve_tipset(tipset_key.clone(), vec![vec![0, 0]]);
+
+        System::assert_last_event(Event::FileCoinModule(crate::Event::TipsetApproved(
+            tipset_key.clone(),
+        )));
+        assert!(TipsetSubmissionProposals::<Test>::get(&tipset_key).is_none());
+        assert_eq!(
+            FileCoinModule::verified_block(&[0, 2]),
+            Some(block_info(vec![0, 2]))
+        );
+        assert_eq!(FileCoinModule::verified_blocks_at_height(1), tipset_key);
+        // the relayers who voted for the approved tipset are rewarded
+        for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+            assert_eq!(PendingRewards::<Test>::get(relayer), RelayerReward::get());
+        }
+    });
+}
+
+#[test]
+fn submit_tipset_vote_fails_invalid_tipset() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_tipset_vote(
+                Origin::signed(RELAYER1),
+                vec![],
+                1,
+                vec![vec![0, 0]],
+                vec![],
+                3
+            ),
+            Error::<Test>::InvalidTipset
+        );
+        assert_err!(
+            FileCoinModule::submit_tipset_vote(
+                Origin::signed(RELAYER1),
+                vec![vec![0, 1], vec![0, 2]],
+                1,
+                vec![vec![0, 0]],
+                vec![block_roots(vec![0, 1])],
+                3
+            ),
+            Error::<Test>::InvalidTipset
+        );
+    });
+}
+
+#[test]
+fn submit_tipset_vote_fails_not_confirmed() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_tipset_vote(
+                Origin::signed(RELAYER1),
+                vec![vec![0, 1]],
+                1,
+                vec![vec![0, 0]],
+                vec![block_roots(vec![0, 1])],
+                2
+            ),
+            Error::<Test>::TipsetNotConfirmed
+        );
+
+        // the head reported by a relayer is only trusted for its own vote
+        assert_ok!(FileCoinModule::submit_tipset_vote(
+            Origin::signed(RELAYER1),
+            vec![vec![0, 1]],
+            1,
+            vec![vec![0, 0]],
+            vec![block_roots(vec![0, 1])],
+            i64::MAX
+        ));
+        assert_err!(
+            FileCoinModule::submit_tipset_vote(
+                Origin::signed(RELAYER2),
+                vec![vec![0, 1]],
+                1,
+                vec![vec![0, 0]],
+                vec![block_roots(vec![0, 1])],
+                2
+            ),
+            Error::<Test>::TipsetNotConfirmed
+        );
+        let tipset_key = vec![vec![0, 1]];
+        assert!(TipsetSubmissionProposals::<Test>::contains_key(&tipset_key));
+        assert!(!VerifiedBlocks::<Test>::contains_key(vec![0, 1]));
+    });
+}
+
+#[test]
+fn submit_tipset_vote_fails_unknown_parent() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_err!(
+            FileCoinModule::submit_tipset_vote(
+                Origin::signed(RELAYER1),
+                vec![vec![0, 1]],
+                1,
+                vec![vec![0, 9]],
+                vec![block_roots(vec![0, 1])],
+                3
+            ),
+            Error::<Test>::UnknownParent
+        );
+    });
+}
+
+#[test]
+fn submit_tipset_vote_drops_fork() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let (_, checkpoint_info) = checkpoint();
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![0, 9],
+            checkpoint_info
+        ));
+
+        approve(vec![0, 1], block_info(vec![0, 1]));
+        approve_tipset(vec![vec![0, 2], vec![0, 3]], vec![vec![0, 9]]);
+
+        System::assert_last_event(Event::FileCoinModule(crate::Event::ForkDetected(
+            1,
+            vec![0, 1],
+            vec![0, 2],
+        )));
+        assert!(!VerifiedBlocks::<Test>::contains_key(&vec![0, 2]));
+        assert!(!VerifiedBlocks::<Test>::contains_key(&vec![0, 3]));
+        assert_eq!(
+            FileCoinModule::verified_blocks_at_height(1),
+            vec![vec![0, 1]]
+        );
+    });
+}
+
+#[test]
+fn submit_tipset_vote_slashes_other_info() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let tipset_key = vec![vec![0, 1]];
+        assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
+        assert_ok!(FileCoinModule::submit_tipset_vote(
+            Origin::signed(RELAYER4),
+            tipset_key.clone(),
+            1,
+            vec![vec![0, 0]],
+            vec![block_roots(vec![0, 2])],
+            3
+        ));
+        approve_tipset(tipset_key, vec![vec![0, 0]]);
+
+        System::assert_has_event(Event::FileCoinModule(crate::Event::RelayerSlashed(
+            RELAYER4,
+            RelayerBond::get(),
+        )));
+        assert_eq!(Balances::reserved_balance(RELAYER4), 0);
+        assert!(!Relayers::<Test>::contains_key(RELAYER4));
+        assert_eq!(PendingRewards::<Test>::get(RELAYER4), 0);
+        // the relayers who voted for the approved info keep their bond and are rewarded
+        assert_eq!(Balances::reserved_balance(RELAYER1), RelayerBond::get());
+        assert!(Relayers::<Test>::contains_key(RELAYER1));
+        assert_eq!(PendingRewards::<Test>::get(RELAYER1), RelayerReward::get());
+    });
+}
+
+#[test]
+fn submit_tipset_vote_does_not_slash_fork() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (_, checkpoint_info) = checkpoint();
+        assert_ok!(FileCoinModule::set_checkpoint(
+            Origin::root(),
+            vec![0, 9],
+            checkpoint_info
+        ));
+        approve(vec![0, 1], block_info(vec![0, 1]));
+
+        let tipset_key = vec![vec![0, 2], vec![0, 3]];
+        assert_ok!(FileCoinModule::add_relayer(Origin::signed(ALICE), RELAYER4));
+        assert_ok!(FileCoinModule::submit_tipset_vote(
+            Origin::signed(RELAYER4),
+            tipset_key.clone(),
+            1,
+            vec![vec![0, 9]],
+            vec![block_roots(vec![0, 4]), block_roots(vec![0, 5])],
+            3
+        ));
+        approve_tipset(tipset_key, vec![vec![0, 9]]);
+
+        // the fork is dropped, the votes for it are not settled
+        assert!(!VerifiedBlocks::<Test>::contains_key(&vec![0, 2]));
+        // the relayers are only rewarded for the block of the verified chain
+        assert_eq!(PendingRewards::<Test>::get(RELAYER2), RelayerReward::get());
+        assert!(Relayers::<Test>::contains_key(RELAYER4));
+        assert_eq!(RelayerBonds::<Test>::get(RELAYER4), RelayerBond::get());
+        assert_eq!(Balances::reserved_balance(RELAYER4), RelayerBond::get());
+    });
+}
+
+#[test]
+fn submit_tipset_vote_unsigned_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pair = Pair::from_seed(&[1; 32]);
+        Relayers::<Test>::insert(pair.public(), ());
+
+        let (payload, signature) = signed_vote(&pair);
+        let call = crate::Call::submit_tipset_vote_unsigned {
+            payload: payload.clone(),
+            signature: signature.clone(),
+        };
+        assert_ok!(FileCoinModule::validate_unsigned(
+            TransactionSource::External,
+            &call
+        ));
+
+        assert_ok!(FileCoinModule::submit_tipset_vote_unsigned(
+            Origin::none(),
+            payload.clone(),
+            signature
+        ));
+        System::assert_last_event(Event::FileCoinModule(crate::Event::TipsetVoteCasted(
+            payload.tipset_key,
+            pair.public(),
+        )));
+
+        // the relayer has voted already
+        assert_eq!(
+            FileCoinModule::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Stale.into()
+        );
+    });
+}
+
+#[test]
+fn validate_unsigned_rejects_invalid_votes() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pair = Pair::from_seed(&[1; 32]);
+        let (payload, signature) = signed_vote(&pair);
+
+        // not a relayer
+        let call = crate::Call::submit_tipset_vote_unsigned {
+            payload: payload.clone(),
+            signature: signature.clone(),
+        };
+        assert_eq!(
+            FileCoinModule::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::BadSigner.into()
+        );
+
+        // the payload does not match the signature
+        Relayers::<Test>::insert(pair.public(), ());
+        let call = crate::Call::submit_tipset_vote_unsigned {
+            payload: TipsetVotePayload { head: 4, ..payload },
+            signature,
+        };
+        assert_eq!(
+            FileCoinModule::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::BadProof.into()
+        );
+
+        // only the votes are unsigned
+        let call = crate::Call::set_vote_threshold { threshold: 1 };
+        assert_eq!(
+            FileCoinModule::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Call.into()
+        );
+    });
+}
+
+#[test]
+fn submit_aggregated_votes_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        let signatures = [1, 2, 3]
+            .iter()
+            .map(|seed| {
+                let pair = Pair::from_seed(&[*seed; 32]);
+                Relayers::<Test>::insert(pair.public(), ());
+                let (payload, signature) = signed_vote(&pair);
+                (payload.public, signature)
+            })
+            .collect::<Vec<_>>();
+
+        // anyone can submit the votes of the relayers
+        let (payload, _) = signed_vote(&Pair::from_seed(&[1; 32]));
+        assert_ok!(FileCoinModule::submit_aggregated_votes(
+            Origin::signed(ALICE),
+            payload.tipset_key.clone(),
+            payload.height,
+            payload.parents,
+            payload.roots,
+            payload.head,
+            signatures
+        ));
+        assert!(!TipsetSubmissionProposals::<Test>::contains_key(
+            &payload.tipset_key
+        ));
+        assert!(VerifiedBlocks::<Test>::contains_key(vec![0, 1]));
+    });
+}
+
+#[test]
+fn submit_aggregated_votes_rejects_invalid_signatures() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pair = Pair::from_seed(&[1; 32]);
+        let other = Pair::from_seed(&[2; 32]);
+        Relayers::<Test>::insert(pair.public(), ());
+        let (payload, signature) = signed_vote(&pair);
+        let (_, other_signature) = signed_vote(&other);
+
+        let submit = |signatures: Vec<(AccountId, Signature)>| {
+            FileCoinModule::submit_aggregated_votes(
+                Origin::signed(ALICE),
+                payload.tipset_key.clone(),
+                payload.height,
+                payload.parents.clone(),
+                payload.roots.clone(),
+                payload.head,
+                signatures,
+            )
+        };
+
+        assert_err!(submit(vec![]), Error::<Test>::InvalidSignature);
+        // the signature is not the one of the relayer
+        assert_err!(
+            submit(vec![(pair.public(), other_signature.clone())]),
+            Error::<Test>::InvalidSignature
+        );
+        assert_err!(
+            submit(vec![
+                (pair.public(), signature.clone()),
+                (other.public(), other_signature)
+            ]),
+            Error::<Test>::NotRelayer
+        );
+        assert_err!(
+            submit(vec![
+                (pair.public(), signature.clone()),
+                (pair.public(), signature)
+            ]),
+            Error::<Test>::AlreadyVoted
+        );
+
+        // none of the votes is counted
+        assert!(!TipsetSubmissionProposals::<Test>::contains_key(
+            &payload.tipset_key
+        ));
+    });
+}
+
+#[test]
+fn submit_aggregated_votes_rejects_invalid_signature_in_the_middle() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pairs = [1, 2, 3]
+            .iter()
+            .map(|seed| Pair::from_seed(&[*seed; 32]))
+            .collect::<Vec<_>>();
+        let mut signatures = pairs
+            .iter()
+            .map(|pair| {
+                Relayers::<Test>::insert(pair.public(), ());
+                let (payload, signature) = signed_vote(pair);
+                (payload.public, signature)
+            })
+            .collect::<Vec<_>>();
+        // the second relayer did not sign the vote
+        signatures[1].1 = signed_vote(&pairs[2]).1;
+
+        let (payload, _) = signed_vote(&pairs[0]);
+        assert_err!(
+            FileCoinModule::submit_aggregated_votes(
+                Origin::signed(ALICE),
+                payload.tipset_key.clone(),
+                payload.height,
+                payload.parents,
+                payload.roots,
+                payload.head,
+                signatures
+            ),
+            Error::<Test>::InvalidSignature
+        );
+
+        // the vote of the first relayer is not counted either
+        assert!(!TipsetSubmissionProposals::<Test>::contains_key(
+            &payload.tipset_key
+        ));
+        assert!(!TipsetProposalVotes::<Test>::contains_key(
+            &payload.tipset_key,
+            pairs[0].public()
+        ));
+    });
+}
+
+/// The vote for the tipset `[[0, 1]]` signed by `pair`
+fn signed_vote(pair: &Pair) -> (TipsetVotePayload<AccountId>, Signature) {
+    let payload = TipsetVotePayload {
+        tipset_key: vec![vec![0, 1]],
+        height: 1,
+        parents: vec![vec![0, 0]],
+        roots: vec![block_roots(vec![0, 1])],
+        head: 3,
+        public: pair.public(),
+    };
+    let signature = pair.sign(&payload.encode());
+    (payload, signature)
+}
+
+/// The roots of `block_info(message_root)`
+fn block_roots(message_root: Vec<u8>) -> BlockRoots {
+    let info = block_info(message_root);
+    BlockRoots {
+        message_root: info.message_root,
+        state_root: info.state_root,
+        receipts_root: info.receipts_root,
+    }
+}
+
+/// Votes for the tipset of epoch 1 `tipset_key` on top of `parents` by all
+/// relayers, the message root of each block is its cid
+fn approve_tipset(tipset_key: Vec<Vec<u8>>, parents: Vec<Vec<u8>>) {
+    let roots = tipset_key
+        .iter()
+        .cloned()
+        .map(block_roots)
+        .collect::<Vec<_>>();
+    for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+        assert_ok!(FileCoinModule::submit_tipset_vote(
+            Origin::signed(relayer),
+            tipset_key.clone(),
+            1,
+            parents.clone(),
+            roots.clone(),
+            3
+        ));
+    }
+}
+
+/// Votes for `block_cid` with `info` by all relayers
+fn approve(block_cid: Vec<u8>, info: VerifiedBlockInfo) {
+    for relayer in [RELAYER1, RELAYER2, RELAYER3] {
+        assert_ok!(FileCoinModule::submit_block_vote(
+            Origin::signed(relayer),
+            block_cid.clone(),
+            info.clone(),
+            HEAD
+        ));
+    }
+}
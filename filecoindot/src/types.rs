@@ -3,11 +3,14 @@
 
 use frame_support::pallet_prelude::*;
 use frame_support::sp_std;
-use frame_system::{Origin, RawOrigin};
+use frame_system::{
+    offchain::{SignedPayload, SigningTypes},
+    Origin, RawOrigin,
+};
 use scale_info::TypeInfo;
 use sp_std::prelude::*;
 // use filecoindot_proofs::{ForestAmtAdaptedNode, HAMTNodeType, ProofVerify, Verify};
-use crate::{Config, Relayers};
+use crate::{BlockCid, Config, MessageRootCid, Relayers};
 
 /// The filecoin block submission proposal
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
@@ -19,6 +22,8 @@ pub(crate) struct BlockSubmissionProposal<AccountId, BlockNumber: PartialOrd> {
     start_block: BlockNumber,
     /// The block number that the proposal ended
     end_block: BlockNumber,
+    /// The number of votes on the proposal
+    votes: u32,
 }
 
 impl<AccountId, BlockNumber: PartialOrd> BlockSubmissionProposal<AccountId, BlockNumber> {
@@ -29,9 +34,19 @@ impl<AccountId, BlockNumber: PartialOrd> BlockSubmissionProposal<AccountId, Bloc
             status: ProposalStatus::Active,
             start_block,
             end_block,
+            votes: 0,
         }
     }
 
+    /// Get the number of votes on the proposal
+    pub fn get_votes(&self) -> u32 {
+        self.votes
+    }
+
+    pub fn add_vote(&mut self) {
+        self.votes = self.votes.saturating_add(1);
+    }
+
     /// Get the status of the proposal
     pub fn get_status(&self) -> &ProposalStatus {
         &self.status
@@ -47,6 +62,121 @@ impl<AccountId, BlockNumber: PartialOrd> BlockSubmissionProposal<AccountId, Bloc
     }
 }
 
+/// The roots of the tries committed to by a filecoin block
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BlockRoots {
+    /// The root cid of the messages included in the block
+    pub message_root: MessageRootCid,
+    /// The root cid of the state tree, i.e. `ParentStateRoot`
+    pub state_root: Vec<u8>,
+    /// The root cid of the message receipts, i.e. `ParentMessageReceipts`
+    pub receipts_root: Vec<u8>,
+}
+
+/// The vote of a relayer for a tipset, signed by the `FilecoindotId` key `public` of
+/// the relayer and submitted in an unsigned transaction
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct TipsetVotePayload<Public> {
+    /// The cids of the blocks of the tipset
+    pub tipset_key: Vec<BlockCid>,
+    /// The epoch of the tipset
+    pub height: i64,
+    /// The cids of the blocks of the parent tipset
+    pub parents: Vec<BlockCid>,
+    /// The roots of each block in the order of the key
+    pub roots: Vec<BlockRoots>,
+    /// The height of the chain head seen by the relayer
+    pub head: i64,
+    /// The key of the relayer
+    pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for TipsetVotePayload<T::Public> {
+    fn public(&self) -> T::Public {
+        self.public.clone()
+    }
+}
+
+/// The metadata of a filecoin block and the roots of the tries committed to by it,
+/// this is the content relayers vote on for a block cid and what is kept once the
+/// block is verified.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct VerifiedBlockInfo {
+    /// The filecoin epoch of the block
+    pub epoch: i64,
+    /// The cids of the blocks of the parent tipset
+    pub parents: Vec<BlockCid>,
+    /// The root cid of the messages included in the block
+    pub message_root: MessageRootCid,
+    /// The root cid of the state tree, i.e. `ParentStateRoot`
+    pub state_root: Vec<u8>,
+    /// The root cid of the message receipts, i.e. `ParentMessageReceipts`
+    pub receipts_root: Vec<u8>,
+}
+
+/// The state of a filecoin actor in the state tree
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ActorState {
+    /// The cid of the code of the actor
+    pub code: Vec<u8>,
+    /// The cid of the state of the actor
+    pub head: Vec<u8>,
+    /// The nonce of the actor
+    pub nonce: u64,
+    /// The balance of the actor in attoFIL
+    pub balance: u128,
+}
+
+/// A storage deal proven in the storage market actor
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct StorageDeal {
+    /// The cid of the piece stored by the deal
+    pub piece_cid: Vec<u8>,
+    /// The address of the client
+    pub client: Vec<u8>,
+    /// The address of the storage provider
+    pub provider: Vec<u8>,
+    /// The epoch the deal starts at
+    pub start_epoch: i64,
+    /// The epoch the deal ends at
+    pub end_epoch: i64,
+}
+
+/// A sector proven in the sectors of a miner actor
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct SectorOnChainInfo {
+    /// The number of the sector
+    pub sector_number: u64,
+    /// The registered seal proof type of the sector
+    pub seal_proof: i64,
+    /// The sealed cid, i.e. CommR, of the sector
+    pub sealed_cid: Vec<u8>,
+    /// The deals stored in the sector
+    pub deal_ids: Vec<u64>,
+    /// The epoch the sector was activated at
+    pub activation: i64,
+    /// The epoch the sector expires at
+    pub expiration: i64,
+}
+
+/// A message receipt proven in the receipts of a block
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct MessageReceipt {
+    /// The exit code of the message, `0` if the message succeeded
+    pub exit_code: u64,
+    /// The value returned by the message
+    pub return_data: Vec<u8>,
+    /// The gas used by the message
+    pub gas_used: i64,
+}
+
+impl MessageReceipt {
+    /// Whether the message of the receipt succeeded
+    pub fn is_success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
 /// The status of the proposal
 /// Expected status transition:
 ///     Active -> Approved -> Executed
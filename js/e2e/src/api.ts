@@ -4,7 +4,11 @@
 import { ApiPromise, WsProvider } from "@polkadot/api";
 import { Keyring } from "@polkadot/keyring";
 import { KeyringPair } from "@polkadot/keyring/types";
-import { rpc as filecoindotRpc, types } from "@chainsafe/filecoindot-types";
+import {
+  Endpoint,
+  rpc as filecoindotRpc,
+  types,
+} from "@chainsafe/filecoindot-types";
 import { EventRecord, Event, Phase } from "@polkadot/types/interfaces";
 import { Balance } from "@polkadot/types/interfaces/runtime";
 import { BN, u8aToHex } from "@polkadot/util";
@@ -79,10 +83,10 @@ export default class Api {
   }
 
   /**
-   * 0.1. set filecoindot rpc endpoint
+   * 0.1. set filecoindot rpc endpoints
    */
-  public async setEndpoint(urls: string[]) {
-    return await (this._.rpc as any).filecoindot.setRpcEndpoint(urls);
+  public async setEndpoint(endpoints: Endpoint[]) {
+    return await (this._.rpc as any).filecoindot.setRpcEndpoint(endpoints);
   }
 
   /**
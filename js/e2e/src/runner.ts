@@ -109,7 +109,7 @@ export default class Runner {
     }
 
     await api.insertAuthor(id);
-    await api.setEndpoint(filecoindotRpc);
+    await api.setEndpoint(filecoindotRpc.map((url) => ({ Url: url })));
     await api.addRelayer();
     await api.depositFund(1000);
     api.events(this.checkEvents);
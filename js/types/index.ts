@@ -2,14 +2,72 @@
  * filecoindot types
  */
 const types = {
+  ActorState: {
+    code: "Vec<u8>",
+    head: "Vec<u8>",
+    nonce: "u64",
+    balance: "u128",
+  },
   BlockCid: "Vec<u8>",
+  BlockRoots: {
+    message_root: "MessageRootCid",
+    state_root: "Vec<u8>",
+    receipts_root: "Vec<u8>",
+  },
+  Endpoint: {
+    _enum: {
+      Url: "Text",
+      WithHeaders: "EndpointWithHeaders",
+    },
+  },
+  EndpointWithHeaders: {
+    url: "Text",
+    headers: "BTreeMap<Text, Text>",
+    token: "Option<Text>",
+  },
   BlockSubmissionProposal: {
     proposer: "AccountId",
     status: "ProposalStatus",
     start_block: "BlockNumber",
     end_block: "BlockNumber",
   },
+  MessageReceipt: {
+    exit_code: "u64",
+    return_data: "Vec<u8>",
+    gas_used: "i64",
+  },
   MessageRootCid: "Vec<u8>",
+  SectorOnChainInfo: {
+    sector_number: "u64",
+    seal_proof: "i64",
+    sealed_cid: "Vec<u8>",
+    deal_ids: "Vec<u64>",
+    activation: "i64",
+    expiration: "i64",
+  },
+  StorageDeal: {
+    piece_cid: "Vec<u8>",
+    client: "Vec<u8>",
+    provider: "Vec<u8>",
+    start_epoch: "i64",
+    end_epoch: "i64",
+  },
+  TipsetKey: "Vec<BlockCid>",
+  TipsetVotePayload: {
+    tipset_key: "Vec<BlockCid>",
+    height: "i64",
+    parents: "Vec<BlockCid>",
+    roots: "Vec<BlockRoots>",
+    head: "i64",
+    public: "MultiSigner",
+  },
+  VerifiedBlockInfo: {
+    epoch: "i64",
+    parents: "Vec<BlockCid>",
+    message_root: "MessageRootCid",
+    state_root: "Vec<u8>",
+    receipts_root: "Vec<u8>",
+  },
 };
 
 /**
@@ -21,8 +79,13 @@ const rpc = {
       description: "set filecoin rpc http endpoint",
       params: [
         {
-          name: "urls",
-          type: "Vec<String>",
+          name: "endpoints",
+          type: "Vec<Endpoint>",
+        },
+        {
+          name: "quorum",
+          type: "u32",
+          isOptional: true,
         },
       ],
       type: "()",
@@ -58,4 +121,18 @@ const rpc = {
   },
 };
 
-export { rpc, types };
+/**
+ * filecoin rpc endpoint, either its url or its url with the headers to send
+ * along each request, `token` is sent as an `Authorization: Bearer` header
+ */
+type Endpoint =
+  | { Url: string }
+  | {
+      WithHeaders: {
+        url: string;
+        headers?: Record<string, string>;
+        token?: string;
+      };
+    };
+
+export { Endpoint, rpc, types };
@@ -282,6 +282,10 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
     pub const OffchainWorkerTimeout: u64 = 1_000_000;
+    pub const ConfirmationDepth: i64 = 900;
+    pub const RelayerBond: Balance = 1_000_000_000_000;
+    pub const RelayerReward: Balance = 1_000_000_000;
+    pub const MaxRelayers: u32 = 100;
 }
 
 // ManagerOrigin as root
@@ -290,9 +294,16 @@ type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
 impl filecoindot::Config for Runtime {
     type ManagerOrigin = ManagerOrigin;
     type Event = Event;
-    type WeightInfo = weights::filecoindot::WeightInfo<Self>;
+    type WeightInfo = weights::filecoindot_estimates::WeightInfo<Self>;
     type AuthorityId = filecoindot::FilecoindotId;
     type OffchainWorkerTimeout = OffchainWorkerTimeout;
+    type ConfirmationDepth = ConfirmationDepth;
+    type Currency = Balances;
+    type RelayerBond = RelayerBond;
+    type Slash = ();
+    type RelayerReward = RelayerReward;
+    type Reward = ();
+    type MaxRelayers = MaxRelayers;
 }
 
 parameter_types! {
@@ -399,7 +410,7 @@ construct_runtime!(
         Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 
         // filecoindot
-        Filecoindot: filecoindot::{Pallet, Call, Config<T>, Storage, Event<T>},
+        Filecoindot: filecoindot::{Pallet, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
         NFT: orml_nft::{Pallet, Config<T>, Storage},
         FilecoindotNFT: filecoindot_nft::{Pallet, Call, Config<T>, Event<T>},
     }
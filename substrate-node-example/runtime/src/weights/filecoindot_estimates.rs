@@ -0,0 +1,245 @@
+// Copyright 2021 ChainSafe Systems
+// SPDX-License-Identifier: LGPL-3.0-only
+
+//! Estimated weights for `filecoindot`
+//!
+//! THESE WEIGHTS ARE NOT GENERATED BY THE SUBSTRATE BENCHMARK CLI. They are written
+//! by hand from the storage accesses of each extrinsic and from the weights of the
+//! extrinsics benchmarked on 2022-02-08, most of which changed since then.
+//! The settlement of the votes of a resolved proposal, i.e. the rewards and the
+//! slashes of the relayers, is charged per voter by `settle_votes`, which has never
+//! been benchmarked and is estimated from the storage accesses of a slash.
+//!
+//! Regenerate them from the benchmarks of the pallet into `weights/filecoindot.rs`
+//! and switch the runtime to it before relying on them:
+//!
+//! ```text
+//! ./target/release/filecoindot-template benchmark -p filecoindot -e '*' \
+//!     --execution wasm --wasm-execution compiled --raw --steps 50 --repeat 20 \
+//!     --heap-pages 4096 --output ./substrate-node-example/runtime/src/weights/filecoindot.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Estimated weight functions for `filecoindot`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> filecoindot::WeightInfo for WeightInfo<T> {
+	// Storage: Filecoindot Relayers (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Filecoindot RelayerCount (r:1 w:1)
+	// Storage: Filecoindot RelayerBonds (r:0 w:1)
+	fn add_relayer() -> Weight {
+		(34_826_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Filecoindot Relayers (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot RelayerCount (r:1 w:1)
+	// Storage: Filecoindot RelayerBonds (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn remove_relayer() -> Weight {
+		(37_102_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Filecoindot RelayerCount (r:1 w:0)
+	// Storage: Filecoindot VoteThreshold (r:0 w:1)
+	fn set_vote_threshold() -> Weight {
+		(11_321_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Filecoindot PendingRewards (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn claim_rewards() -> Weight {
+		(31_544_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Filecoindot Relayers (r:1 w:0)
+	// Storage: Filecoindot VerifiedBlocks (r:2 w:1)
+	// Storage: Filecoindot BlockSubmissionProposals (r:1 w:1)
+	// Storage: Filecoindot VotingPeriod (r:1 w:0)
+	// Storage: Filecoindot BlockProposalVotes (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot MessageRootCidCounter (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	fn submit_block_vote() -> Weight {
+		(55_102_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: Filecoindot Relayers (r:1 w:0)
+	// Storage: Filecoindot VerifiedBlocks (r:2 w:1)
+	// Storage: Filecoindot BlockSubmissionProposals (r:1 w:1)
+	// Storage: Filecoindot VotingPeriod (r:1 w:0)
+	// Storage: Filecoindot BlockProposalVotes (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot MessageRootCidCounter (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	fn submit_block_header() -> Weight {
+		(63_557_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:1)
+	// Storage: Filecoindot BlockSubmissionProposals (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot MessageRootCidCounter (r:0 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	fn close_block_proposal() -> Weight {
+		(26_912_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Filecoindot Relayers (r:1 w:0)
+	// Storage: Filecoindot VerifiedBlocks (r:2 w:1)
+	// Storage: Filecoindot TipsetSubmissionProposals (r:1 w:1)
+	// Storage: Filecoindot VotingPeriod (r:1 w:0)
+	// Storage: Filecoindot TipsetProposalVotes (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot TipsetInfoCounter (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	fn submit_tipset_vote(n: u32, ) -> Weight {
+		(58_634_000 as Weight)
+			.saturating_add((4_917_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Filecoindot Relayers (r:1 w:0)
+	// Storage: Filecoindot TipsetProposalVotes (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocks (r:2 w:1)
+	// Storage: Filecoindot TipsetSubmissionProposals (r:1 w:1)
+	// Storage: Filecoindot VotingPeriod (r:1 w:0)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot TipsetInfoCounter (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	fn submit_aggregated_votes(n: u32, r: u32, ) -> Weight {
+		(61_207_000 as Weight)
+			.saturating_add((4_932_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((71_480_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+	}
+	// Storage: Filecoindot TipsetSubmissionProposals (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	// Storage: Filecoindot TipsetInfoCounter (r:1 w:1)
+	// Storage: Filecoindot TipsetProposalVotes (r:0 w:1)
+	fn close_tipset_proposal() -> Weight {
+		(27_305_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Filecoindot BlockSubmissionProposals (r:0 w:1)
+	// Storage: Filecoindot MessageRootCidCounter (r:1 w:1)
+	// Storage: Filecoindot BlockProposalVotes (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	// Storage: Filecoindot Relayers (r:1 w:1)
+	// Storage: Filecoindot RelayerBonds (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Filecoindot RelayerCount (r:1 w:1)
+	// Storage: Filecoindot VoteThreshold (r:1 w:0)
+	fn settle_votes(v: u32, ) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((40_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(v as Weight)))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	// Storage: Filecoindot Checkpoint (r:0 w:1)
+	fn set_checkpoint() -> Weight {
+		(18_245_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:1)
+	// Storage: Filecoindot VerifiedBlocksByHeight (r:1 w:1)
+	fn verify_ancestry(n: u32, ) -> Weight {
+		(6_318_000 as Weight)
+			.saturating_add((21_476_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_receipt() -> Weight {
+		(7_567_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_state() -> Weight {
+		(125_020_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_message() -> Weight {
+		(125_149_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_actor_state() -> Weight {
+		(98_455_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_deal() -> Weight {
+		(131_716_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_sector() -> Weight {
+		(134_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_message_receipt() -> Weight {
+		(72_614_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_receipt_value() -> Weight {
+		(7_912_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_state_value() -> Weight {
+		(126_384_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_message_value() -> Weight {
+		(126_301_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_receipt_exclusion() -> Weight {
+		(72_614_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_state_exclusion() -> Weight {
+		(126_384_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Filecoindot VerifiedBlocks (r:1 w:0)
+	fn verify_message_exclusion() -> Weight {
+		(126_301_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+}
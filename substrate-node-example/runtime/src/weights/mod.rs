@@ -1,4 +1,4 @@
 // Copyright 2021 ChainSafe Systems
 // SPDX-License-Identifier: LGPL-3.0-only
 #![allow(clippy::unnecessary_cast)]
-pub mod filecoindot;
+pub mod filecoindot_estimates;
@@ -9,6 +9,17 @@ export const types = {
         start_block: "BlockNumber",
         end_block: "BlockNumber",
     },
+    Endpoint: {
+        _enum: {
+            Url: "Text",
+            WithHeaders: "EndpointWithHeaders",
+        },
+    },
+    EndpointWithHeaders: {
+        url: "Text",
+        headers: "BTreeMap<Text, Text>",
+        token: "Option<Text>",
+    },
     MessageRootCid: "Vec<u8>",
 };
 
@@ -21,8 +32,13 @@ export const types = {
             description: "set filecoin rpc http endpoint",
             params: [
                 {
-                    name: "urls",
-                    type: "Vec<String>",
+                    name: "endpoints",
+                    type: "Vec<Endpoint>",
+                },
+                {
+                    name: "quorum",
+                    type: "u32",
+                    isOptional: true,
                 },
             ],
             type: "()",